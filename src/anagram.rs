@@ -0,0 +1,269 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Finds the words a multiset of letters can spell, each letter used at most
+ * as many times as it's held
+ *
+ * This is the board solve without paths: a rack of Scrabble tiles, or the
+ * dice of a board taken as a bag. Any word a board holds can be spelled from
+ * its rack, so the anagrams of a board are a cheap upper bound on its words.
+ *
+ * The trie is walked with the letter counts in place of the board. A held
+ * letter is always spent before a blank, as a blank could stand for more.
+ */
+
+use std::collections::HashSet;
+
+use boggle::*;
+use boggle_util;
+use solver::spelled_len;
+use trie::NodeType;
+use trie::Trie;
+
+const Q: u8 = b'q' - b'a';
+const U: u8 = b'u' - b'a';
+
+/// Letters to spell words from
+#[derive(Clone, Debug, Default)]
+pub struct Rack {
+    counts: [usize; boggle_util::ALPHABET_SIZE],
+    /// Blanks, grouped by the letters they may stand for
+    blanks: Vec<(Cell, usize)>,
+    tiles: usize,
+    /// Whether a 'q' is a Qu die, rather than a Q tile wanting a U tile too
+    dice: bool,
+}
+
+impl Rack {
+    /// Reads letter tiles and '?' or '*' blanks, a Q and a U being two tiles
+    pub fn parse(s: &str) -> Result<Rack, &'static str> {
+        let mut rack = Rack::default();
+        for c in s.chars().filter(|c| !c.is_whitespace()) {
+            if boggle_util::is_wildcard(c) {
+                rack.add(Cell::wildcard());
+            } else {
+                let v = boggle_util::char_to_idx(c).ok_or("Racks may only hold letters and '?' or '*' blanks")?;
+                rack.add(Cell::letter(v));
+            }
+        }
+        Ok(rack)
+    }
+
+    /// The dice of a board, a die with a choice of letters being a blank limited to them
+    pub fn of(board: &RadixBoggleBoard) -> Rack {
+        let mut rack = Rack { dice: true, ..Rack::default() };
+        for i in 0..board.width() * board.height() {
+            let cell = board.get(i);
+            if cell != HOLE {
+                rack.add(cell);
+            }
+        }
+        rack
+    }
+
+    fn add(&mut self, cell: Cell) {
+        self.tiles += 1;
+        if cell.is_letter() {
+            self.counts[cell.letters().next().unwrap() as usize] += 1;
+        } else {
+            match self.blanks.iter_mut().find(|&&mut (c, _)| c == cell) {
+                Some(blank) => blank.1 += 1,
+                None => self.blanks.push((cell, 1)),
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles
+    }
+
+    /// How many tiles a word takes from this rack
+    fn spends(&self, word: &[u8]) -> usize {
+        if self.dice { word.len() } else { spelled_len(word) }
+    }
+}
+
+/// A word the rack spells
+#[derive(Clone, Debug, PartialEq)]
+pub struct Anagram {
+    pub id: usize,
+    /// Letters of the word, 'q' standing for "qu"
+    pub word: Vec<u8>,
+    /// Positions in the spelled out word taken by a blank
+    pub blanks: Vec<usize>,
+}
+
+impl Anagram {
+    /// Length of the word as a player spells it, Qu counting as two letters
+    pub fn len(&self) -> usize {
+        spelled_len(&self.word)
+    }
+
+    /// Spells out the word, letters taken by a blank uppercased
+    pub fn render(&self) -> String {
+        self.word.iter()
+            .flat_map(|&v| boggle_util::idx_to_face(v).chars())
+            .enumerate()
+            .map(|(i, c)| if self.blanks.contains(&i) { c.to_ascii_uppercase() } else { c })
+            .collect()
+    }
+}
+
+/// Every word the rack spells, alphabetically
+pub fn solve(trie: &Trie, rack: &Rack) -> Vec<Anagram> {
+    let mut search = Search {
+        rack: rack.clone(),
+        spent: 0,
+        word: Vec::new(),
+        blanks: Vec::new(),
+        seen: HashSet::new(),
+        found: Vec::new(),
+    };
+    search.walk(trie);
+
+    let mut found = search.found;
+    found.sort_by(|a, b| a.word.cmp(&b.word));
+    found
+}
+
+/// The words spelled with every tile of the rack
+pub fn exact(trie: &Trie, rack: &Rack) -> Vec<Anagram> {
+    let mut found = solve(trie, rack);
+    found.retain(|anagram| rack.spends(&anagram.word) == rack.len());
+    found
+}
+
+struct Search {
+    /// What's left of the rack
+    rack: Rack,
+    /// Tiles taken from the rack so far
+    spent: usize,
+    word: Vec<u8>,
+    blanks: Vec<usize>,
+    /// Ids found so far, as blanks can spell a word more than one way
+    seen: HashSet<usize>,
+    found: Vec<Anagram>,
+}
+
+impl Search {
+    fn walk(&mut self, trie: &Trie) {
+        let at = spelled_len(&self.word);
+        for (child, v) in trie.iter() {
+            // A trie 'q' is one Qu die, but a Q and a U tile
+            if v == Q && !self.rack.dice {
+                self.take(child, v, &[Q, U], at);
+            } else {
+                self.take(child, v, &[v], at);
+            }
+        }
+    }
+
+    /// Takes the tiles spelling trie letter v, one at a time, then visits its node
+    fn take(&mut self, node: &Trie, v: u8, tiles: &[u8], at: usize) {
+        let (t, rest) = match tiles.split_first() {
+            Some((&t, rest)) => (t, rest),
+            None => return self.visit(node, v)
+        };
+
+        self.spent += 1;
+        if self.rack.counts[t as usize] > 0 {
+            self.rack.counts[t as usize] -= 1;
+            self.take(node, v, rest, at + 1);
+            self.rack.counts[t as usize] += 1;
+        } else {
+            // A blank taking a Qu die stands for both letters of its face
+            let width = if self.rack.dice && t == Q { 2 } else { 1 };
+            for g in 0..self.rack.blanks.len() {
+                let (cell, left) = self.rack.blanks[g];
+                if left > 0 && cell.contains(t) {
+                    self.rack.blanks[g].1 -= 1;
+                    self.blanks.extend(at..at + width);
+                    self.take(node, v, rest, at + width);
+                    self.blanks.truncate(self.blanks.len() - width);
+                    self.rack.blanks[g].1 += 1;
+                }
+            }
+        }
+        self.spent -= 1;
+    }
+
+    fn visit(&mut self, node: &Trie, v: u8) {
+        self.word.push(v);
+
+        if let NodeType::Word(id) = node.node_type() {
+            if self.seen.insert(id) {
+                self.found.push(Anagram { id, word: self.word.clone(), blanks: self.blanks.clone() });
+            }
+        }
+        if node.height() > 0 && self.spent < self.rack.tiles {
+            self.walk(node);
+        }
+
+        self.word.pop();
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use dictionary::Dictionary;
+    use solver;
+    use super::*;
+
+    fn dictionary() -> Dictionary {
+        Dictionary::read(["tea", "eat", "ate", "teat", "state", "taste", "quest", "quiet", "seat", "sea"].iter().map(|w| w.to_string()))
+    }
+
+    #[test]
+    fn spends_letters_then_blanks() {
+        let dictionary = dictionary();
+        let words = |rack: &str| -> Vec<String> {
+            solve(dictionary.trie(), &Rack::parse(rack).unwrap()).iter().map(|a| a.render()).collect()
+        };
+
+        assert_eq!(words("tae"), vec!["ate", "eat", "tea"]);
+        assert_eq!(words("taet"), vec!["ate", "eat", "tea", "teat"]);
+        assert_eq!(words("QuEST"), vec!["quest"]);
+        assert_eq!(words("stae?"), vec!["ate", "eat", "sea", "seat", "staTe", "tasTe", "tea", "teaT"]);
+        assert_eq!(words("qest"), Vec::<String>::new());
+        assert_eq!(words("qest?"), vec!["Ate", "eAt", "qUest", "seA", "seAt", "teA"]);
+        assert!(Rack::parse("te-a").is_err());
+
+        let exact: Vec<String> = exact(dictionary.trie(), &Rack::parse("tea").unwrap()).iter().map(|a| a.render()).collect();
+        assert_eq!(exact, vec!["ate", "eat", "tea"]);
+    }
+
+    #[test]
+    fn bounds_the_board_solve() {
+        let dictionary = dictionary();
+        let board = RadixBoggleBoard::from(&SimpleBoggleBoard::read("qst\neat\n.?s".lines()).unwrap());
+
+        let anagrams: Vec<usize> = solve(dictionary.trie(), &Rack::of(&board)).iter().map(|a| a.id).collect();
+        let solution = solver::solve_radix(dictionary.trie(), &board);
+        assert!(!solution.is_empty());
+        assert!(solution.iter().all(|found| anagrams.contains(&found.id)));
+    }
+}
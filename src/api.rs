@@ -0,0 +1,140 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * The JSON forms of boards, solutions and verdicts shared by the server, the
+ * engine and the solve reports
+ *
+ * A board is either a string with one row per line, an array of rows, or a
+ * one line board code (see boggle::code). A path is an array of
+ * [row, column] pairs.
+ */
+
+use boggle::*;
+use dictionary::Dictionary;
+use game::*;
+use json;
+use json::Json;
+use solver;
+
+/// Every word on the board, with its path and points
+pub fn solve(dictionary: &Dictionary, board: &RadixBoggleBoard) -> Json {
+    let solution = solver::solve_radix(dictionary.trie(), board);
+
+    let mut score = 0;
+    let words: Vec<Json> = solution.iter().map(|found| {
+        let points = points(found.len());
+        score += points;
+        json::object(vec![
+            ("word", found.render(|pos| !board.get(pos).is_letter()).into()),
+            ("path", path_to_json(&found.path, board.width())),
+            ("points", points.into()),
+        ])
+    }).collect();
+
+    json::object(vec![
+        ("width", board.width().into()),
+        ("height", board.height().into()),
+        ("count", words.len().into()),
+        ("score", score.into()),
+        ("words", Json::Array(words)),
+    ])
+}
+
+/// A verdict for each word, as if one player submitted them in order
+pub fn validate(dictionary: &Dictionary, board: &RadixBoggleBoard, words: Option<&Json>) -> Result<Json, &'static str> {
+    let words = words.and_then(|w| w.as_array()).ok_or("Expected \"words\" to be an array")?;
+
+    let mut validator = Validator::new(dictionary, board);
+    let mut score = 0;
+    let mut results = Vec::with_capacity(words.len());
+    for word in words {
+        let word = word.as_str().ok_or("Expected \"words\" to be strings")?;
+        let verdict = validator.validate(word);
+        results.push(verdict_to_json(word, &verdict, board.width()));
+        if let Verdict::Accepted(_, points) = verdict {
+            score += points;
+        }
+    }
+
+    Ok(json::object(vec![
+        ("score", score.into()),
+        ("results", Json::Array(results)),
+    ]))
+}
+
+/// Reads a board in any of the forms the API takes, the readers refusing boards past MAX_CELLS
+pub fn board_from_json(board: Option<&Json>) -> Result<SimpleBoggleBoard, &'static str> {
+    match board {
+        Some(Json::String(s)) if !s.trim().contains('\n') => code::read(s),
+        Some(Json::String(s)) => SimpleBoggleBoard::read(s.lines()),
+        Some(Json::Array(rows)) => {
+            let rows: Option<Vec<&str>> = rows.iter().map(|row| row.as_str()).collect();
+            SimpleBoggleBoard::read(rows.ok_or("Expected board rows to be strings")?.into_iter())
+        },
+        _ => Err("Expected \"board\" to be a string or an array of rows")
+    }
+}
+
+pub fn board_to_json(board: &SimpleBoggleBoard) -> Json {
+    Json::Array(board.to_string().lines().map(Json::from).collect())
+}
+
+/// A path as [row, column] pairs
+pub fn path_to_json(path: &[usize], width: usize) -> Json {
+    Json::Array(path.iter().map(|&i| vec![i / width, i % width].into()).collect())
+}
+
+pub fn verdict_to_json(word: &str, verdict: &Verdict, width: usize) -> Json {
+    let mut members = vec![
+        ("word", word.into()),
+        ("verdict", verdict.name().into()),
+    ];
+    if let Verdict::Accepted(ref path, points) = *verdict {
+        members.push(("path", path_to_json(path, width)));
+        members.push(("points", points.into()));
+    }
+    json::object(members)
+}
+
+
+#[cfg(test)]
+mod test {
+    use json::Json;
+    use super::*;
+
+    #[test]
+    fn reads_boards_in_every_form() {
+        let forms = [r#""qet\nena\n..s""#, r#"["qet", "ena", "..s"]"#, r#""qet/ena/..s""#];
+        for form in forms.iter() {
+            let board = board_from_json(Some(&Json::parse(form).unwrap())).unwrap();
+            assert_eq!(board_to_json(&board).to_string(), r#"["qet","ena","..s"]"#);
+        }
+
+        assert!(board_from_json(Some(&Json::parse("1").unwrap())).is_err());
+        assert!(board_from_json(None).is_err());
+        assert_eq!(path_to_json(&[0, 4, 8], 3).to_string(), "[[0,0],[1,1],[2,2]]");
+    }
+}
@@ -0,0 +1,234 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Solves many boards in one run, sharing one dictionary between them
+ *
+ * A board file holds boards separated by blank lines, each written one row
+ * per line as SimpleBoggleBoard::read expects. A block whose lines are all
+ * 16, 25 or 36 characters long is instead read as one square board per line,
+ * e.g. a 4x4 board as `qetenas.abcdefgh`. (So a 16 wide board with no
+ * blank lines between rows should be written with a blank line after it.)
+ *
+ * Every board is solved on its own rayon job; boards are independent, so
+ * this scales far better than splitting the trie for tiny boards.
+ */
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+use std::time::Instant;
+
+use rayon::prelude::*;
+
+use boggle::*;
+use dictionary::Dictionary;
+use game::points;
+use solver;
+use solver::Solution;
+
+/// Lengths of a board written on one line, see code::read_square
+const ONE_LINE_SIZES: [usize; 3] = [16, 25, 36];
+
+/// A board and where it came from
+pub struct Named {
+    pub name: String,
+    pub board: SimpleBoggleBoard,
+}
+
+pub struct BoardResult {
+    pub name: String,
+    pub board: SimpleBoggleBoard,
+    pub solution: Solution,
+    pub score: u32,
+    pub elapsed: Duration,
+}
+
+impl BoardResult {
+    /// The longest word on the board, the first found on a tie
+    pub fn longest(&self) -> Option<String> {
+        let mut longest: Option<&solver::Found> = None;
+        for found in self.solution.iter() {
+            if longest.is_none_or(|l| found.len() > l.len()) {
+                longest = Some(found);
+            }
+        }
+        longest.map(|found| found.render(|pos| !self.board.get(pos).is_letter()))
+    }
+}
+
+/// Totals across every board in a batch
+#[derive(Debug, PartialEq)]
+pub struct Stats {
+    pub boards: usize,
+    pub words: usize,
+    pub min_words: usize,
+    pub max_words: usize,
+    pub score: u64,
+    pub max_score: u32,
+    /// Time spent solving, summed over boards
+    pub solve_time: Duration,
+}
+
+impl Stats {
+    pub fn of(results: &[BoardResult]) -> Self {
+        Stats {
+            boards: results.len(),
+            words: results.iter().map(|r| r.solution.len()).sum(),
+            min_words: results.iter().map(|r| r.solution.len()).min().unwrap_or(0),
+            max_words: results.iter().map(|r| r.solution.len()).max().unwrap_or(0),
+            score: results.iter().map(|r| r.score as u64).sum(),
+            max_score: results.iter().map(|r| r.score).max().unwrap_or(0),
+            solve_time: results.iter().map(|r| r.elapsed).sum(),
+        }
+    }
+
+    pub fn mean_words(&self) -> f64 {
+        if self.boards == 0 { 0.0 } else { self.words as f64 / self.boards as f64 }
+    }
+
+    pub fn mean_score(&self) -> f64 {
+        if self.boards == 0 { 0.0 } else { self.score as f64 / self.boards as f64 }
+    }
+}
+
+/// Reads every board in a file, naming them `name#1`, `name#2`... if there are several
+pub fn read_boards(name: &str, text: &str) -> Result<Vec<Named>, String> {
+    let mut boards = Vec::new();
+    let mut block: Vec<&str> = Vec::new();
+
+    for line in text.lines().map(|line| line.trim()).chain(Some("")) {
+        if !line.is_empty() {
+            block.push(line);
+            continue;
+        }
+        if block.is_empty() {
+            continue;
+        }
+
+        if block.iter().all(|line| ONE_LINE_SIZES.contains(&line.len())) {
+            for line in &block {
+                boards.push(code::read_square(line).map_err(|e| format!("{}: {}", name, e))?);
+            }
+        } else {
+            boards.push(SimpleBoggleBoard::read(block.iter().cloned()).map_err(|e| format!("{}: {}", name, e))?);
+        }
+        block.clear();
+    }
+
+    let single = boards.len() == 1;
+    Ok(boards.into_iter().enumerate().map(|(i, board)| {
+        let name = if single { name.to_owned() } else { format!("{}#{}", name, i + 1) };
+        Named { name, board }
+    }).collect())
+}
+
+/// Reads a board file, or every file in a directory in name order
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Vec<Named>> {
+    let path = path.as_ref();
+    let mut files = if path.is_dir() {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                files.push(entry.path());
+            }
+        }
+        files.sort();
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let mut boards = Vec::new();
+    for file in files.drain(..) {
+        let name = file.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let text = fs::read_to_string(&file)?;
+        boards.extend(read_boards(&name, &text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?);
+    }
+    Ok(boards)
+}
+
+/// Solves every board in parallel, keeping input order
+pub fn solve_all(dictionary: &Dictionary, boards: Vec<Named>) -> Vec<BoardResult> {
+    let mut results = Vec::with_capacity(boards.len());
+    boards.into_par_iter().map(|named| {
+        let start = Instant::now();
+        let radix = RadixBoggleBoard::from(&named.board);
+        let solution = solver::solve_radix(dictionary.trie(), &radix);
+        let score = solution.iter().map(|found| points(found.len())).sum();
+
+        BoardResult {
+            name: named.name,
+            board: named.board,
+            solution,
+            score,
+            elapsed: start.elapsed(),
+        }
+    }).collect_into(&mut results);
+    results
+}
+
+
+#[cfg(test)]
+mod test {
+    use fixture;
+    use super::read_boards;
+    use super::solve_all;
+    use super::Stats;
+
+    #[test]
+    fn reads_blocks_and_one_line_boards() {
+        let text = "qet\nena\n..s\n\n\nqetenas.abcdefgh\nabcdefghijklmnop\n\nab\ncd\n";
+        let boards = read_boards("boards.txt", text).unwrap();
+
+        let names: Vec<&str> = boards.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["boards.txt#1", "boards.txt#2", "boards.txt#3", "boards.txt#4"]);
+        assert_eq!(boards[0].board.to_string(), "qet\nena\n..s\n");
+        assert_eq!(boards[1].board.to_string(), "qete\nnas.\nabcd\nefgh\n");
+        assert_eq!(boards[3].board.width(), 2);
+
+        assert_eq!(read_boards("one.txt", "ab\ncd").unwrap()[0].name, "one.txt");
+        assert!(read_boards("bad.txt", "ab\ncd\n\na1\ncd").is_err());
+    }
+
+    #[test]
+    fn solves_boards_in_order() {
+        let dictionary = fixture::dictionary();
+        let boards = read_boards("b", &format!("{}\n\ntan\n...\n\nxx\nxx", fixture::BOARD)).unwrap();
+        let results = solve_all(&dictionary, boards);
+
+        let counts: Vec<usize> = results.iter().map(|r| r.solution.len()).collect();
+        assert_eq!(counts, vec![4, 1, 0]);
+        assert_eq!(results[0].longest(), Some("queen".to_owned()));
+        assert_eq!(results[2].longest(), None);
+
+        let stats = Stats::of(&results);
+        assert_eq!((stats.boards, stats.words, stats.min_words, stats.max_words), (3, 5, 0, 4));
+        assert_eq!((stats.score, stats.max_score), (6, 5));
+        assert_eq!(stats.mean_words(), 5.0 / 3.0);
+    }
+}
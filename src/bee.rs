@@ -0,0 +1,239 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Spelling bee puzzles: seven letters around a hexagon, the one in the
+ * middle in every word
+ *
+ * Letters may be used any number of times, words are at least four letters
+ * long, and a pangram uses all seven. The trie is walked with the letters as
+ * a mask, a 'q' in the trie standing for "qu" and so needing both.
+ *
+ * Puzzles are generated from the letters of a word with seven distinct
+ * letters, so each has at least one pangram, retrying until the number of
+ * words falls in the range asked for.
+ */
+
+use std::collections::HashSet;
+use std::fmt;
+
+use bitset::BitSet32;
+use boggle_util;
+use dictionary::Dictionary;
+use game::bee_points;
+use rand::Rng;
+use solver::spelled_len;
+use trie::NodeType;
+use trie::Trie;
+
+/// Letters in a puzzle
+pub const LETTERS: u32 = 7;
+
+/// Shortest word allowed, counting Qu as two letters
+pub const MIN_LENGTH: usize = 4;
+
+const Q: u8 = b'q' - b'a';
+const U: u8 = b'u' - b'a';
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Puzzle {
+    pub center: u8,
+    /// Every letter of the puzzle, the center included
+    pub letters: BitSet32,
+}
+
+impl Puzzle {
+    /// Reads seven distinct letters, the center letter being one of them
+    pub fn parse(letters: &str, center: char) -> Result<Puzzle, &'static str> {
+        let mut set = BitSet32::new();
+        for c in letters.chars() {
+            let v = boggle_util::char_to_idx(c).ok_or("Puzzle letters must be letters")?;
+            if set.get(v as u32) {
+                return Err("Puzzle letters must be distinct");
+            }
+            set.add(v as u32);
+        }
+        if set.cardinality() != LETTERS {
+            return Err("Puzzles have seven letters");
+        }
+        match boggle_util::char_to_idx(center) {
+            Some(v) if set.get(v as u32) => Ok(Puzzle { center: v, letters: set }),
+            _ => Err("The center letter must be one of the puzzle letters")
+        }
+    }
+
+    /// Whether a trie letter can be spelled, a 'q' needing its 'u'
+    fn allows(&self, v: u8) -> bool {
+        self.letters.get(v as u32) && (v != Q || self.letters.get(U as u32))
+    }
+}
+
+/// Writes the center letter first and uppercased, then the others
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", (b'A' + self.center) as char)?;
+        for v in self.letters.iter_ones().filter(|&v| v as u8 != self.center) {
+            write!(f, "{}", (b'a' + v as u8) as char)?;
+        }
+        Ok(())
+    }
+}
+
+/// A word of the puzzle
+#[derive(Clone, Debug, PartialEq)]
+pub struct Answer {
+    pub id: usize,
+    /// Spelled out, "qu" and all
+    pub word: String,
+    pub points: u32,
+    pub pangram: bool,
+}
+
+/// Every word of the puzzle, alphabetically
+pub fn solve(trie: &Trie, puzzle: &Puzzle) -> Vec<Answer> {
+    let mut answers = Vec::new();
+    let mut word = Vec::new();
+    walk(trie, puzzle, &mut word, BitSet32::new(), &mut answers);
+    answers
+}
+
+fn walk(trie: &Trie, puzzle: &Puzzle, word: &mut Vec<u8>, used: BitSet32, answers: &mut Vec<Answer>) {
+    for (child, v) in trie.iter().filter(|&(_, v)| puzzle.allows(v)) {
+        let mut used = used;
+        used.add(v as u32);
+        if v == Q {
+            used.add(U as u32);
+        }
+        word.push(v);
+
+        if let NodeType::Word(id) = child.node_type() {
+            let len = spelled_len(word);
+            if len >= MIN_LENGTH && used.get(puzzle.center as u32) {
+                let pangram = used == puzzle.letters;
+                answers.push(Answer {
+                    id,
+                    word: word.iter().map(|&v| boggle_util::idx_to_face(v)).collect(),
+                    points: bee_points(len, pangram),
+                    pangram,
+                });
+            }
+        }
+        if child.height() > 0 {
+            walk(child, puzzle, word, used, answers);
+        }
+
+        word.pop();
+    }
+}
+
+/*
+ * Picks a puzzle with between `min` and `max` words. Gives up after a number
+ * of attempts, or when no word has seven distinct letters.
+ */
+pub fn generate<R: Rng>(dictionary: &Dictionary, min: usize, max: usize, attempts: usize, rng: &mut R)
+        -> Option<(Puzzle, Vec<Answer>)> {
+    let pangrams = pangram_letters(dictionary.trie());
+    if pangrams.is_empty() {
+        return None;
+    }
+
+    for _ in 0..attempts {
+        let letters = pangrams[rng.gen_range(0, pangrams.len())];
+        let choices: Vec<u32> = letters.iter_ones().collect();
+        let puzzle = Puzzle { center: choices[rng.gen_range(0, choices.len())] as u8, letters };
+
+        let answers = solve(dictionary.trie(), &puzzle);
+        if min <= answers.len() && answers.len() <= max {
+            return Some((puzzle, answers));
+        }
+    }
+    None
+}
+
+/// The distinct letter sets of words with exactly seven letters
+fn pangram_letters(trie: &Trie) -> Vec<BitSet32> {
+    let mut seen = HashSet::new();
+    let mut sets = Vec::new();
+    for (word, _) in trie.prefixed("").into_iter().flatten() {
+        let mut letters = BitSet32::new();
+        for b in word.bytes() {
+            letters.add((b - b'a') as u32);
+            if b == b'q' {
+                letters.add(U as u32);
+            }
+        }
+        if letters.cardinality() == LETTERS && seen.insert(letters) {
+            sets.push(letters);
+        }
+    }
+    sets
+}
+
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand::XorShiftRng;
+
+    use dictionary::Dictionary;
+    use super::*;
+
+    fn dictionary() -> Dictionary {
+        let words = ["paint", "pain", "pant", "patina", "tapa", "captain", "pinata", "antic", "tint", "nip", "panic", "quip", "equip"];
+        Dictionary::read(words.iter().map(|w| w.to_string()))
+    }
+
+    #[test]
+    fn solves_around_the_center() {
+        let dictionary = dictionary();
+        let puzzle = Puzzle::parse("acinpte", 'p').unwrap();
+        assert_eq!(puzzle.to_string(), "Paceint");
+
+        let answers = solve(dictionary.trie(), &puzzle);
+        let words: Vec<(&str, u32, bool)> = answers.iter().map(|a| (a.word.as_str(), a.points, a.pangram)).collect();
+        assert_eq!(words, vec![("captain", 7, false), ("pain", 1, false), ("paint", 5, false), ("panic", 5, false),
+                               ("pant", 1, false), ("patina", 6, false), ("pinata", 6, false), ("tapa", 1, false)]);
+
+        // A 'q' needs its 'u' among the letters
+        let puzzle = Puzzle::parse("quipeat", 'q').unwrap();
+        let words: Vec<String> = solve(dictionary.trie(), &puzzle).into_iter().map(|a| a.word).collect();
+        assert_eq!(words, vec!["equip", "quip"]);
+        assert!(Puzzle::parse("quipea", 'q').is_err());
+        assert!(Puzzle::parse("quipeaa", 'q').is_err());
+        assert!(Puzzle::parse("quipeat", 'z').is_err());
+    }
+
+    #[test]
+    fn generates_puzzles_with_a_pangram() {
+        let dictionary = Dictionary::read(["antique", "quiet", "quite", "tine", "neat", "tune", "unit", "taint", "pain"].iter().map(|w| w.to_string()));
+        let mut rng = XorShiftRng::from_seed([7, 11, 13, 17]);
+
+        let (puzzle, answers) = generate(&dictionary, 2, 10, 50, &mut rng).unwrap();
+        assert!(answers.iter().any(|a| a.pangram));
+        assert!(answers.len() >= 2);
+        assert!(answers.iter().all(|a| a.word.contains((b'a' + puzzle.center) as char)));
+        assert!(generate(&dictionary, 20, 30, 50, &mut rng).is_none());
+    }
+}
@@ -28,12 +28,14 @@ use std::cmp::max;
 const TWO_POW_64: u64 = 0x8000000000000000;
 const MAX: u64        = 0xFFFFFFFFFFFFFFFF;
 
+#[derive(Default)]
 pub struct BitSet {
     data: Vec<u64>,
     len: usize
 }
 
 impl BitSet {
+    #[allow(dead_code)]
     pub fn new() -> Self {
         BitSet {
             data: Vec::new(),
@@ -51,8 +53,9 @@ impl BitSet {
         self.len
     }
 
+    #[allow(dead_code)]
     pub fn get(&self, i: usize) -> bool {
-        let ref data = self.data;
+        let data = &self.data;
         let (idx, off) = Self::idx(i);
 
         if data.len() > idx {
@@ -68,7 +71,7 @@ impl BitSet {
     }
 
     pub fn add(&mut self, i: usize) {
-        let ref mut data = self.data;
+        let data = &mut self.data;
         let (idx, off) = Self::idx(i);
 
         if idx >= data.len() {
@@ -83,7 +86,7 @@ impl BitSet {
     #[allow(dead_code)]
     pub fn remove(&mut self, i: usize) {
         if i < self.len() {
-            let ref mut data = self.data;
+            let data = &mut self.data;
             let (idx, off) = Self::idx(i);
 
             // i < self.len() guarantees in bounds 
@@ -105,7 +108,7 @@ impl BitSet {
 
     #[allow(dead_code)]
     pub fn clear(&mut self) {
-        let ref mut data = self.data;
+        let data = &mut self.data;
 
         self.len = 0;
         for datum in data.iter_mut() {
@@ -113,7 +116,7 @@ impl BitSet {
         }
     }
 
-    pub fn iter_ones(&self) -> IndexIter {
+    pub fn iter_ones(&self) -> IndexIter<'_> {
         IndexIter::new(self)
     }
 }
@@ -129,7 +132,7 @@ impl<'a> Iter<'a> {
     #[allow(dead_code)]
     fn new(bitset: &'a BitSet) -> Self {
         Iter {
-            bitset: bitset,
+            bitset,
             idx: 0,
             off: 0,
         }
@@ -166,7 +169,7 @@ pub struct IndexIter<'a> {
 impl<'a> IndexIter<'a> {
     fn new(bitset: &'a BitSet) -> Self {
         IndexIter {
-            bitset: bitset,
+            bitset,
             idx: 0,
             off: 0,
         }
@@ -177,7 +180,7 @@ impl<'a> Iterator for IndexIter<'a> {
     type Item=usize;
 
     fn next(&mut self) -> Option<usize> {
-        let ref data = self.bitset.data;
+        let data = &self.bitset.data;
 
         while self.idx < data.len() {
             match unsafe { data.get_unchecked(self.idx) } & MAX >> self.off {
@@ -204,6 +207,7 @@ impl<'a> Iterator for IndexIter<'a> {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod test {
     use super::BitSet;
 
@@ -31,13 +31,13 @@ use std::fmt::Formatter;
 // Fixed Size BitSet //
 ///////////////////////
 
-#[derive(Copy, Clone, PartialEq)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
 pub struct BitSet32 {
     value: u32,
 }
 
 impl BitSet32 {
-    pub fn new() -> Self {
+    pub const fn new() -> Self {
         BitSet32 {
             value: 0,
         }
@@ -71,11 +71,11 @@ impl BitSet32 {
     }
 
     #[allow(dead_code)]
-    pub fn iter(&self) -> Iter32 {
+    pub fn iter(&self) -> Iter32<'_> {
         Iter32::new(self)
     }
 
-    pub fn iter_ones(&self) -> IndexIter32 {
+    pub fn iter_ones(&self) -> IndexIter32<'_> {
         IndexIter32::new(self)
     }
 
@@ -101,7 +101,7 @@ impl<'a> Iter32<'a> {
     #[allow(dead_code)]
     fn new(bitset: &'a BitSet32) -> Self {
         Iter32 {
-            bitset: bitset,
+            bitset,
             i: 0,
         }
     }
@@ -131,7 +131,7 @@ pub struct IndexIter32<'a> {
 impl<'a> IndexIter32<'a> {
     fn new(bitset: &'a BitSet32) -> Self {
         IndexIter32 {
-            bitset: bitset,
+            bitset,
             i: 0,
         }
     }
@@ -161,6 +161,7 @@ impl<'a> Iterator for IndexIter32<'a> {
 
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison, clippy::while_let_on_iterator)]
 mod test {
     use super::BitSet32;
 
@@ -23,6 +23,7 @@
  * POSSIBILITY OF SUCH DAMAGE.
  */
 
+#[allow(clippy::module_inception)]
 mod bitset;
 mod bitset_32;
 
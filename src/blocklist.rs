@@ -0,0 +1,135 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Words we won't show to players, however legal
+ *
+ * A blocklist file holds one entry per line, blank lines and lines starting
+ * with '#' being skipped. An entry is a word, or a pattern where '*' stands
+ * for any run of letters and '?' for exactly one, e.g. `slur*` also blocks
+ * "slurs". Entries are matched against words spelled in full, "qu" and all.
+ */
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::path::Path;
+
+#[derive(Default)]
+pub struct Blocklist {
+    words: HashSet<String>,
+    patterns: Vec<String>,
+}
+
+impl Blocklist {
+    pub fn read<I>(lines: I) -> Self where I: Iterator<Item=String> {
+        let mut blocklist = Blocklist::default();
+        for line in lines {
+            let entry = line.trim().to_lowercase();
+            if entry.is_empty() || entry.starts_with('#') {
+                continue;
+            }
+            if entry.contains('*') || entry.contains('?') {
+                blocklist.patterns.push(entry);
+            } else {
+                blocklist.words.insert(entry);
+            }
+        }
+        blocklist
+    }
+
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let lines: io::Result<Vec<String>> = BufReader::new(file).lines().collect();
+        Ok(Self::read(lines?.into_iter()))
+    }
+
+    /// Is the word, spelled in lowercase, blocked
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(word) || self.patterns.iter().any(|p| glob(p.as_bytes(), word.as_bytes()))
+    }
+}
+
+/*
+ * Matches a word against a pattern of '*' and '?' wildcards. On a mismatch
+ * we retry from the last '*', letting it take one more letter, which is
+ * enough as a '*' can only ever need to grow.
+ */
+
+fn glob(pattern: &[u8], word: &[u8]) -> bool {
+    let (mut p, mut w) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while w < word.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == word[w]) {
+            p += 1;
+            w += 1;
+        } else if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, w));
+            p += 1;
+        } else if let Some((sp, sw)) = star {
+            p = sp + 1;
+            w = sw + 1;
+            star = Some((sp, sw + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::Blocklist;
+    use super::glob;
+
+    #[test]
+    fn globs() {
+        assert!(glob(b"abc", b"abc"));
+        assert!(!glob(b"abc", b"abcd"));
+        assert!(glob(b"a?c", b"abc"));
+        assert!(!glob(b"a?c", b"ac"));
+        assert!(glob(b"a*", b"a"));
+        assert!(glob(b"*b*", b"abc"));
+        assert!(glob(b"a*b*c", b"aXbYbZc"));
+        assert!(!glob(b"a*b*c", b"aXbYbZ"));
+        assert!(glob(b"*", b""));
+    }
+
+    #[test]
+    fn blocks_words_and_patterns() {
+        let blocklist = Blocklist::read(["# rude", "Darn", "", "heck*", "?uiz"].iter().map(|l| l.to_string()));
+
+        assert!(blocklist.contains("darn"));
+        assert!(!blocklist.contains("darned"));
+        assert!(blocklist.contains("heck") && blocklist.contains("heckled"));
+        assert!(blocklist.contains("quiz"));
+        assert!(!blocklist.contains("rude"));
+    }
+}
@@ -0,0 +1,101 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+use std::fmt;
+
+use boggle_util;
+use bitset::BitSet32;
+use bitset::IndexIter32;
+
+/// The letters a board position may stand for.
+///
+/// A regular die face is a single letter, a blank die is every letter and a
+/// hole (no die at all) is the empty set. Anything in between is a restricted
+/// blank, e.g. "any vowel".
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Cell {
+    letters: BitSet32,
+}
+
+/// A position with no die. Never matches a letter.
+pub const HOLE: Cell = Cell { letters: BitSet32::new() };
+
+impl Cell {
+    pub fn letter(v: u8) -> Self {
+        let mut letters = BitSet32::new();
+        letters.add(v as u32);
+        Cell { letters }
+    }
+
+    pub fn wildcard() -> Self {
+        Self::of(0..boggle_util::ALPHABET_SIZE as u8)
+    }
+
+    pub fn of<I>(letters: I) -> Self where I: IntoIterator<Item=u8> {
+        let mut cell = HOLE;
+        for v in letters {
+            cell.letters.add(v as u32);
+        }
+        cell
+    }
+
+    /// True for a plain die face, false for holes and blanks
+    pub fn is_letter(&self) -> bool {
+        self.letters.cardinality() == 1
+    }
+
+    pub fn contains(&self, v: u8) -> bool {
+        self.letters.get(v as u32)
+    }
+
+    pub fn letters(&self) -> IndexIter32<'_> {
+        self.letters.iter_ones()
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        HOLE
+    }
+}
+
+/// Writes the cell as it's written in a board file
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if *self == HOLE {
+            write!(f, ".")
+        } else if *self == Cell::wildcard() {
+            write!(f, "?")
+        } else if self.is_letter() {
+            write!(f, "{}", (b'a' + self.letters().next().unwrap() as u8) as char)
+        } else {
+            write!(f, "[")?;
+            for v in self.letters() {
+                write!(f, "{}", (b'a' + v as u8) as char)?;
+            }
+            write!(f, "]")
+        }
+    }
+}
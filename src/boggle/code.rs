@@ -0,0 +1,247 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Compact one line board notations, for passing boards around by hand
+ *
+ *   square  every cell row after row, e.g. `qetenasxabcdefgh` for a 4x4 board.
+ *           Must hold 16, 25 or 36 cells.
+ *   rows    the rows joined by '/', e.g. `qet/ena/..s`. Any size, rows may
+ *           be ragged like in a board file.
+ *   url     `<width>x<height>-<dice>-<cells>`, e.g. `3x3-custom-qetena..s`,
+ *           using only characters that are safe in a URL.
+ *
+ * The square and rows forms use the cell syntax of board files, a 'q' being
+ * the Qu die. As "Qu" is how the face is printed, it's read as one cell too.
+ *
+ * In the url form a hole is '.', a blank is '_' and any other set of letters
+ * is its size followed by its letters, e.g. `3aei`. The dice are those the
+ * board was rolled from, see dice::Set.
+ */
+
+use boggle_util;
+
+use super::Cell;
+use super::HOLE;
+use super::MAX_CELLS;
+use super::SimpleBoggleBoard;
+use super::dice::Set;
+
+/// Cells in a board written in the square form: 4x4, 5x5 and 6x6
+const SQUARE_SIZES: [usize; 3] = [16, 25, 36];
+
+/// Reads a board in any of the forms, telling them apart by their separators
+pub fn read(code: &str) -> Result<SimpleBoggleBoard, &'static str> {
+    let code = code.trim();
+    if code.contains('-') {
+        decode(code).map(|(board, _)| board)
+    } else if code.contains('/') {
+        read_rows(code)
+    } else {
+        read_square(code)
+    }
+}
+
+pub fn read_square(code: &str) -> Result<SimpleBoggleBoard, &'static str> {
+    let cells = SimpleBoggleBoard::read_row(&code.replace("Qu", "q"))?;
+    if !SQUARE_SIZES.contains(&cells.len()) {
+        return Err("Expected 16, 25 or 36 cells");
+    }
+
+    let size = (cells.len() as f64).sqrt() as usize;
+    Ok(from_cells(size, size, cells))
+}
+
+/// Writes a 4x4, 5x5 or 6x6 board in the square form
+pub fn write_square(board: &SimpleBoggleBoard) -> Option<String> {
+    if board.width() != board.height() || !SQUARE_SIZES.contains(&(board.width() * board.height())) {
+        return None;
+    }
+    Some(board.iter().map(|cell| cell.to_string()).collect())
+}
+
+pub fn read_rows(code: &str) -> Result<SimpleBoggleBoard, &'static str> {
+    let code = code.replace("Qu", "q");
+    SimpleBoggleBoard::read(code.split('/'))
+}
+
+pub fn write_rows(board: &SimpleBoggleBoard) -> String {
+    board.to_string().trim_end().replace('\n', "/")
+}
+
+pub fn decode(code: &str) -> Result<(SimpleBoggleBoard, Set), &'static str> {
+    let mut parts = code.splitn(3, '-');
+    let (size, dice, encoded) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(size), Some(dice), Some(encoded)) => (size, dice, encoded),
+        _ => return Err("Expected <width>x<height>-<dice>-<cells>")
+    };
+
+    let (width, height) = match size.find('x') {
+        Some(i) => match (size[..i].parse::<usize>(), size[i + 1..].parse::<usize>()) {
+            (Ok(width), Ok(height)) => (width, height),
+            _ => return Err("Invalid board size")
+        },
+        None => return Err("Invalid board size")
+    };
+    if width < 2 || height < 2 {
+        return Err("Board width and height must be >= 2");
+    }
+    // Every cell takes at least one character, so the size can be checked before allocating
+    let size = match width.checked_mul(height) {
+        Some(size) if size <= MAX_CELLS && size <= encoded.len() => size,
+        Some(size) if size <= MAX_CELLS => return Err("Cell count doesn't match board size"),
+        _ => return Err("Board too large")
+    };
+
+    let set = Set::from_name(dice).ok_or("Unknown dice set")?;
+
+    let mut cells = Vec::with_capacity(size);
+    let mut chars = encoded.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => cells.push(HOLE),
+            '_' => cells.push(Cell::wildcard()),
+            'a'..='z' => cells.push(Cell::letter(c as u8 - b'a')),
+            '0'..='9' => {
+                let mut count = c.to_digit(10).unwrap() as usize;
+                while let Some(d) = chars.peek().and_then(|d| d.to_digit(10)) {
+                    count = count * 10 + d as usize;
+                    chars.next();
+                    if count > boggle_util::ALPHABET_SIZE {
+                        return Err("Letter set too long");
+                    }
+                }
+                let mut letters = Vec::with_capacity(count);
+                for _ in 0..count {
+                    match chars.next() {
+                        Some(c @ 'a'..='z') => letters.push(c as u8 - b'a'),
+                        _ => return Err("Letter set too short")
+                    }
+                }
+                cells.push(Cell::of(letters));
+            },
+            _ => return Err("Invalid chars")
+        }
+    }
+
+    if cells.len() != size {
+        return Err("Cell count doesn't match board size");
+    }
+    Ok((from_cells(width, height, cells), set))
+}
+
+pub fn encode(board: &SimpleBoggleBoard, set: Set) -> String {
+    let mut code = format!("{}x{}-{}-", board.width(), board.height(), set.name());
+    for cell in board.iter() {
+        if *cell == HOLE {
+            code.push('.');
+        } else if *cell == Cell::wildcard() {
+            code.push('_');
+        } else if cell.is_letter() {
+            code.push_str(&cell.to_string());
+        } else {
+            code.push_str(&cell.letters().count().to_string());
+            code.extend(cell.letters().map(|v| (b'a' + v as u8) as char));
+        }
+    }
+    code
+}
+
+fn from_cells(width: usize, height: usize, cells: Vec<Cell>) -> SimpleBoggleBoard {
+    let mut board = SimpleBoggleBoard::new(width, height);
+    for (i, cell) in cells.into_iter().enumerate() {
+        board.set(i, cell);
+    }
+    board
+}
+
+
+#[cfg(test)]
+mod test {
+    use boggle::SimpleBoggleBoard;
+    use boggle::dice::Set;
+    use super::*;
+
+    fn board(rows: &str) -> SimpleBoggleBoard {
+        SimpleBoggleBoard::read(rows.lines()).unwrap()
+    }
+
+    #[test]
+    fn square_round_trips() {
+        let b = board("qete\nnas.\n?bcd\nef[aeiou]h");
+
+        assert_eq!(write_square(&b).unwrap(), "qetenas.?bcdef[aeiou]h");
+        assert_eq!(read_square("qetenas.?bcdef[aeiou]h").unwrap().to_string(), b.to_string());
+        assert_eq!(read_square("QuetenAs.?bcdef[aeiou]h").unwrap().to_string(), b.to_string());
+        assert_eq!(read_square("abcdefghijklmnopqrstuvwxy").unwrap().width(), 5);
+
+        assert!(read_square("abcdefghijklmno").is_err());
+        assert!(write_square(&board("abc\ndef")).is_none());
+    }
+
+    #[test]
+    fn rows_round_trip() {
+        let b = board("qet\nena\n..s\nab");
+
+        assert_eq!(write_rows(&b), "qet/ena/..s/ab.");
+        assert_eq!(read_rows("Quet/ena/..s/ab").unwrap().to_string(), b.to_string());
+        assert!(read_rows("abc").is_err());
+        assert!(read_rows("ab//cd").is_err());
+    }
+
+    #[test]
+    fn url_round_trips() {
+        let b = board("qe?\n.n[aeiou]\nab");
+        let code = encode(&b, Set::Custom);
+
+        assert_eq!(code, "3x3-custom-qe_.n5aeiouab.");
+        let (decoded, set) = decode(&code).unwrap();
+        assert_eq!(decoded.to_string(), b.to_string());
+        assert_eq!(set, Set::Custom);
+
+        let b = board("[abcdefghijkl]q\nxy");
+        let (decoded, set) = decode(&encode(&b, Set::Classic)).unwrap();
+        assert_eq!(decoded.to_string(), b.to_string());
+        assert_eq!(set, Set::Classic);
+
+        assert!(decode("2x2-classic-abc").is_err());
+        assert!(decode("2x2-fancy-abcd").is_err());
+        assert!(decode("2x2-big-ab3cd").is_err());
+        assert!(decode("2-big-abcd").is_err());
+        assert!(decode("1x2-custom-ab").is_err());
+        assert!(decode("100000x100000-custom-a").is_err());
+        assert!(decode("18446744073709551615x2-custom-a").is_err());
+        assert!(decode("2x2-custom-a99999999999999999999999b").is_err());
+    }
+
+    #[test]
+    fn reads_any_form() {
+        let b = board("qete\nnasx\nabcd\nefgh");
+
+        for code in ["qetenasxabcdefgh", "qete/nasx/abcd/efgh", "4x4-classic-qetenasxabcdefgh"].iter() {
+            assert_eq!(read(code).unwrap().to_string(), b.to_string());
+        }
+    }
+}
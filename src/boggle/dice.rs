@@ -0,0 +1,116 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Dice sets, each die is written as its six faces where 'q' is the Qu face
+ */
+
+use rand::Rng;
+
+use boggle_util;
+
+use super::Cell;
+use super::SimpleBoggleBoard;
+
+pub type Die = &'static str;
+
+/// The 16 dice of the current 4x4 edition
+pub const CLASSIC: [Die; 16] = [
+    "aaeegn", "abbjoo", "achops", "affkps",
+    "aoottw", "cimotu", "deilrx", "delrvy",
+    "distty", "eeghnw", "eeinsu", "ehrtvw",
+    "eiosst", "elrtty", "himnqu", "hlnnrz",
+];
+
+/// The 25 dice of Big Boggle (5x5)
+pub const BIG: [Die; 25] = [
+    "aaafrs", "aaeeee", "aafirs", "adennn", "aeeeem",
+    "aeegmu", "aegmnn", "afirsy", "bjkqxz", "ccenst",
+    "ceiilt", "ceilpt", "ceipst", "ddhnot", "dhhlor",
+    "dhlnor", "dhlnor", "eiiitt", "emottt", "ensssu",
+    "fiprsy", "gorrvw", "iprrry", "nootuw", "ooottu",
+];
+
+/// Which dice a board was rolled from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Set {
+    Classic,
+    Big,
+    /// Not rolled from a known set, e.g. typed in by hand
+    Custom,
+}
+
+impl Set {
+    /// The set `generate` rolls a board of this size from
+    pub fn for_size(width: usize, height: usize) -> Set {
+        if width * height <= CLASSIC.len() { Set::Classic } else { Set::Big }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Set::Classic => "classic",
+            Set::Big => "big",
+            Set::Custom => "custom",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Set> {
+        match name {
+            "classic" => Some(Set::Classic),
+            "big" => Some(Set::Big),
+            "custom" => Some(Set::Custom),
+            _ => None
+        }
+    }
+}
+
+/// Picks one of the die's faces at random
+pub fn roll<R: Rng>(die: Die, rng: &mut R) -> Cell {
+    let faces = die.as_bytes();
+    let face = faces[rng.gen_range(0, faces.len())];
+    Cell::letter(boggle_util::ascii_byte_to_idx(face) as u8)
+}
+
+/*
+ * Shakes the dice into a new board. The classic set is used for boards up to
+ * 4x4 and Big Boggle's for anything larger, reusing dice when there are more
+ * cells than dice.
+ */
+
+pub fn generate<R: Rng>(width: usize, height: usize, rng: &mut R) -> SimpleBoggleBoard {
+    let set: &[Die] = match Set::for_size(width, height) {
+        Set::Classic => &CLASSIC,
+        _ => &BIG
+    };
+
+    let mut dice: Vec<Die> = set.iter().cycle().take(width * height).cloned().collect();
+    rng.shuffle(&mut dice);
+
+    let mut board = SimpleBoggleBoard::new(width, height);
+    for (i, die) in dice.into_iter().enumerate() {
+        board.set(i, roll(die, rng));
+    }
+    board
+}
@@ -23,18 +23,23 @@
  * POSSIBILITY OF SUCH DAMAGE.
  */
 
+mod cell;
+pub mod code;
+pub mod dice;
 mod simple_board;
 mod radix_board;
 
+pub use self::cell::*;
 pub use self::simple_board::*;
 pub use self::radix_board::*;
 
 
 // Box overhead slows down the algo by 2x!
 // Leaving this unimplemented!
+#[allow(dead_code)]
 pub trait BoggleBoard {
 	fn width(&self) -> usize;
 	fn height(&self) -> usize;
-    fn any(&self, v: u8) -> Box<Iterator<Item=usize>>;
-    fn neighbors(&self, i: usize, v: u8) -> Box<Iterator<Item=usize>>;
+    fn any(&self, v: u8) -> Box<dyn Iterator<Item=usize>>;
+    fn neighbors(&self, i: usize, v: u8) -> Box<dyn Iterator<Item=usize>>;
 }
\ No newline at end of file
@@ -27,7 +27,12 @@ use boggle_util;
 use bitset::BitSet;
 use bitset::IndexIter;
 
+use rand::Rng;
+
+use super::Cell;
 use super::SimpleBoggleBoard;
+use super::dice;
+use super::dice::Die;
 
 /*
  * Can this be done cleaner with Enums and some sort of EnumSet
@@ -54,28 +59,19 @@ pub struct RadixBoggleBoard {
     /// Serves as a precomupted adjacency matrix filtered by value
     /// (Used to quickly find all the neighbors of a cell of a specified value)
     cells: Box<[RadixBoggleCell]>,
+    /// The cell each position was set to
+    /// (Used to tell blanks from plain letters when rendering words)
+    values: Box<[Cell]>,
 }
 
 impl RadixBoggleBoard {
     pub fn new(width: usize, height: usize) -> Self {
-        use std::mem;
-        use std::ptr;
-
-        let mut alpha: [BitSet; boggle_util::ALPHABET_SIZE];
-        unsafe {
-            alpha = mem::uninitialized();
-
-            for element in alpha.iter_mut() {
-                let bs = BitSet::new();
-                ptr::write(element, bs);
-            }
-        }
-
         RadixBoggleBoard {
-            width: width,
-            height: height,
-            alpha: alpha,
+            width,
+            height,
+            alpha: Default::default(),
             cells: vec![ Default::default(); width * height ].into_boxed_slice(),
+            values: vec![ Default::default(); width * height ].into_boxed_slice(),
         }
     }
 
@@ -91,7 +87,19 @@ impl RadixBoggleBoard {
         dst
     }
 
-    pub fn any(&self, v: u8) -> IndexIter {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, i: usize) -> Cell {
+        self.values[i]
+    }
+
+    pub fn any(&self, v: u8) -> IndexIter<'_> {
         self.alpha[v as usize].iter_ones()
     }
 
@@ -104,93 +112,135 @@ impl RadixBoggleBoard {
     }
 
     #[inline]
-    fn mask_cell(&mut self, v: usize, i: usize, mask: u8) {
-        self.cells[i][v] |= mask;
+    fn mask_cell(&mut self, v: usize, i: usize, mask: u8, on: bool) {
+        if on {
+            self.cells[i][v] |= mask;
+        } else {
+            self.cells[i][v] &= !mask;
+        }
+    }
+
+
+    /// A blank is masked with the flag of every letter it stands for,
+    /// so the search tries every trie child there. A hole has no letters,
+    /// so nothing is masked around it.
+    ///
+    /// The letters of the cell being replaced are unmasked first, so a board
+    /// can be edited in place and stay identical to one rebuilt from scratch.
+    pub fn set(&mut self, i: usize, cell: Cell) {
+        let old = self.values[i];
+        for v in old.letters() {
+            self.mask_letter(i, v as u8, false);
+        }
+
+        self.values[i] = cell;
+        for v in cell.letters() {
+            self.mask_letter(i, v as u8, true);
+        }
+    }
+
+    /// Exchanges the cells at i and j
+    #[allow(dead_code)]
+    pub fn swap(&mut self, i: usize, j: usize) {
+        let (a, b) = (self.values[i], self.values[j]);
+        self.set(i, b);
+        self.set(j, a);
     }
 
+    /// Replaces the cell at i with a random face of die, returning the new cell
+    #[allow(dead_code)]
+    pub fn reroll<R: Rng>(&mut self, i: usize, die: Die, rng: &mut R) -> Cell {
+        let cell = dice::roll(die, rng);
+        self.set(i, cell);
+        cell
+    }
+
+    fn mask_letter(&mut self, i: usize, v: u8, on: bool) {
+        if on {
+            self.alpha[v as usize].add(i);
+        } else {
+            self.alpha[v as usize].remove(i);
+        }
 
-    pub fn set(&mut self, i: usize, v: u8) {
-        self.alpha[v as usize].add(i);
-        
         let w = self.width;
         let v = v as usize;
 
-        // Mask each neighbor of i with the flag for v (relative to i)
+        // Mask (or unmask) each neighbor of i with the flag for v (relative to i)
         match i {
             // northwest corner
             0 => {
-                self.mask_cell(v, i  +1, FLAG_WEST);
-                self.mask_cell(v, i+w  , FLAG_NORTH);
-                self.mask_cell(v, i+w+1, FLAG_NORTHWEST);
+                self.mask_cell(v, i  +1, FLAG_WEST, on);
+                self.mask_cell(v, i+w  , FLAG_NORTH, on);
+                self.mask_cell(v, i+w+1, FLAG_NORTHWEST, on);
             },
 
             // northeast corner
             x if x == self.width -1 => {
-                self.mask_cell(v, i  -1, FLAG_EAST);
-                self.mask_cell(v, i+w-1, FLAG_NORTHEAST);
-                self.mask_cell(v, i+w  , FLAG_NORTH);
+                self.mask_cell(v, i  -1, FLAG_EAST, on);
+                self.mask_cell(v, i+w-1, FLAG_NORTHEAST, on);
+                self.mask_cell(v, i+w  , FLAG_NORTH, on);
             },
 
             // southwest corner
             x if x == self.width * (self.height - 1) => {
-                self.mask_cell(v, i-w  , FLAG_SOUTH);
-                self.mask_cell(v, i-w+1, FLAG_SOUTHWEST);
-                self.mask_cell(v, i  +1, FLAG_WEST);
+                self.mask_cell(v, i-w  , FLAG_SOUTH, on);
+                self.mask_cell(v, i-w+1, FLAG_SOUTHWEST, on);
+                self.mask_cell(v, i  +1, FLAG_WEST, on);
             },
 
             // southeast corner
             x if x == self.width * self.height - 1 => {
-                self.mask_cell(v, i-w-1, FLAG_SOUTHEAST);
-                self.mask_cell(v, i-w  , FLAG_SOUTH);
-                self.mask_cell(v, i  -1, FLAG_EAST);
+                self.mask_cell(v, i-w-1, FLAG_SOUTHEAST, on);
+                self.mask_cell(v, i-w  , FLAG_SOUTH, on);
+                self.mask_cell(v, i  -1, FLAG_EAST, on);
             },
 
             // north edge
             x if x < self.width => {
-                self.mask_cell(v, i  -1, FLAG_EAST);
-                self.mask_cell(v, i  +1, FLAG_WEST);
-                self.mask_cell(v, i+w-1, FLAG_NORTHEAST);
-                self.mask_cell(v, i+w  , FLAG_NORTH);
-                self.mask_cell(v, i+w+1, FLAG_NORTHWEST);
+                self.mask_cell(v, i  -1, FLAG_EAST, on);
+                self.mask_cell(v, i  +1, FLAG_WEST, on);
+                self.mask_cell(v, i+w-1, FLAG_NORTHEAST, on);
+                self.mask_cell(v, i+w  , FLAG_NORTH, on);
+                self.mask_cell(v, i+w+1, FLAG_NORTHWEST, on);
             },
 
             // south edge
             x if x > self.width * (self.height - 1) => {
-                self.mask_cell(v, i-w-1, FLAG_SOUTHEAST);
-                self.mask_cell(v, i-w  , FLAG_SOUTH);
-                self.mask_cell(v, i-w+1, FLAG_SOUTHWEST);
-                self.mask_cell(v, i  -1, FLAG_EAST);
-                self.mask_cell(v, i  +1, FLAG_WEST);
+                self.mask_cell(v, i-w-1, FLAG_SOUTHEAST, on);
+                self.mask_cell(v, i-w  , FLAG_SOUTH, on);
+                self.mask_cell(v, i-w+1, FLAG_SOUTHWEST, on);
+                self.mask_cell(v, i  -1, FLAG_EAST, on);
+                self.mask_cell(v, i  +1, FLAG_WEST, on);
             },
 
             // west edge
             x if x % self.width == 0 => {
-                self.mask_cell(v, i-w  , FLAG_SOUTH);
-                self.mask_cell(v, i-w+1, FLAG_SOUTHWEST);
-                self.mask_cell(v, i  +1, FLAG_WEST);
-                self.mask_cell(v, i+w  , FLAG_NORTH);
-                self.mask_cell(v, i+w+1, FLAG_NORTHWEST);
+                self.mask_cell(v, i-w  , FLAG_SOUTH, on);
+                self.mask_cell(v, i-w+1, FLAG_SOUTHWEST, on);
+                self.mask_cell(v, i  +1, FLAG_WEST, on);
+                self.mask_cell(v, i+w  , FLAG_NORTH, on);
+                self.mask_cell(v, i+w+1, FLAG_NORTHWEST, on);
             },
 
             // east edge
             x if x % self.width == self.width - 1 => {
-                self.mask_cell(v, i-w-1, FLAG_SOUTHEAST);
-                self.mask_cell(v, i-w  , FLAG_SOUTH);
-                self.mask_cell(v, i  -1, FLAG_EAST);
-                self.mask_cell(v, i+w-1, FLAG_NORTHEAST);
-                self.mask_cell(v, i+w  , FLAG_NORTH);
+                self.mask_cell(v, i-w-1, FLAG_SOUTHEAST, on);
+                self.mask_cell(v, i-w  , FLAG_SOUTH, on);
+                self.mask_cell(v, i  -1, FLAG_EAST, on);
+                self.mask_cell(v, i+w-1, FLAG_NORTHEAST, on);
+                self.mask_cell(v, i+w  , FLAG_NORTH, on);
             },
 
             // interior
             _ => {
-                self.mask_cell(v, i-w-1, FLAG_SOUTHEAST);
-                self.mask_cell(v, i-w  , FLAG_SOUTH);
-                self.mask_cell(v, i-w+1, FLAG_SOUTHWEST);
-                self.mask_cell(v, i  -1, FLAG_EAST);
-                self.mask_cell(v, i  +1, FLAG_WEST);
-                self.mask_cell(v, i+w-1, FLAG_NORTHEAST);
-                self.mask_cell(v, i+w  , FLAG_NORTH);
-                self.mask_cell(v, i+w+1, FLAG_NORTHWEST);
+                self.mask_cell(v, i-w-1, FLAG_SOUTHEAST, on);
+                self.mask_cell(v, i-w  , FLAG_SOUTH, on);
+                self.mask_cell(v, i-w+1, FLAG_SOUTHWEST, on);
+                self.mask_cell(v, i  -1, FLAG_EAST, on);
+                self.mask_cell(v, i  +1, FLAG_WEST, on);
+                self.mask_cell(v, i+w-1, FLAG_NORTHEAST, on);
+                self.mask_cell(v, i+w  , FLAG_NORTH, on);
+                self.mask_cell(v, i+w+1, FLAG_NORTHWEST, on);
             }
         }
     }
@@ -221,3 +271,73 @@ impl Iterator for RadixNeighborIter {
         }
     }
 }
+
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+    use rand::SeedableRng;
+    use rand::XorShiftRng;
+
+    use boggle_util;
+    use boggle::Cell;
+    use boggle::HOLE;
+    use boggle::SimpleBoggleBoard;
+    use boggle::dice;
+    use super::RadixBoggleBoard;
+
+    fn random_cell<R: Rng>(rng: &mut R) -> Cell {
+        match rng.gen_range(0, 10) {
+            0 => HOLE,
+            1 => Cell::wildcard(),
+            2 => Cell::of(vec![rng.gen_range(0, 26), rng.gen_range(0, 26)]),
+            _ => Cell::letter(rng.gen_range(0, 26)),
+        }
+    }
+
+    fn assert_same(a: &RadixBoggleBoard, b: &RadixBoggleBoard, size: usize) {
+        for v in 0..boggle_util::ALPHABET_SIZE as u8 {
+            assert_eq!(a.any(v).collect::<Vec<usize>>(), b.any(v).collect::<Vec<usize>>());
+            for i in 0..size {
+                assert_eq!(a.neighbors(i, v).collect::<Vec<usize>>(),
+                           b.neighbors(i, v).collect::<Vec<usize>>());
+            }
+        }
+    }
+
+    #[test]
+    fn mutations_match_rebuilt_board() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+
+        for &(width, height) in &[(2, 2), (4, 4), (5, 3), (7, 9)] {
+            let size = width * height;
+            let mut simple = SimpleBoggleBoard::new(width, height);
+            let mut radix = RadixBoggleBoard::from(&simple);
+
+            for _ in 0..500 {
+                let i = rng.gen_range(0, size);
+                match rng.gen_range(0, 3) {
+                    0 => {
+                        let cell = random_cell(&mut rng);
+                        simple.set(i, cell);
+                        radix.set(i, cell);
+                    },
+                    1 => {
+                        let j = rng.gen_range(0, size);
+                        let (a, b) = (simple.get(i), simple.get(j));
+                        simple.set(i, b);
+                        simple.set(j, a);
+                        radix.swap(i, j);
+                    },
+                    _ => {
+                        let die = dice::CLASSIC[rng.gen_range(0, dice::CLASSIC.len())];
+                        let cell = radix.reroll(i, die, &mut rng);
+                        simple.set(i, cell);
+                    }
+                }
+
+                assert_same(&radix, &RadixBoggleBoard::from(&simple), size);
+            }
+        }
+    }
+}
@@ -26,8 +26,15 @@
 use boggle_util;
 
 use std;
+use std::fmt;
 
-type SimpleBoggleCell = u8;
+use super::Cell;
+use super::HOLE;
+
+type SimpleBoggleCell = Cell;
+
+/// Boards are refused past this many cells when read, as soon as the rows read so far pass it
+pub const MAX_CELLS: usize = 256 * 256;
 
 #[derive(Debug)]
 pub struct SimpleBoggleBoard {
@@ -37,72 +44,128 @@ pub struct SimpleBoggleBoard {
 }
 
 impl SimpleBoggleBoard {
-    #[allow(dead_code)]
     pub fn new(width: usize, height: usize) -> Self {
         SimpleBoggleBoard {
-            width: width,
-            height: height,
+            width,
+            height,
             cells: vec![ Default::default(); width * height ].into_boxed_slice(),
         }
     }
 
-    pub fn read<'a, I>(mut lines: I) -> Result<SimpleBoggleBoard, &'static str> where I: Iterator<Item=&'a str> {
-        if let Some(first) = lines.next() {
-            let first = &first.trim().to_lowercase();
-            if !boggle_util::is_alpha(first) {
-                return Err("Invalid chars");
+    /// Reads a board with one line per row.
+    ///
+    /// A `.` or `#` marks a hole: a position with no die, which is never
+    /// entered and so breaks adjacency. Rows shorter than the widest row are
+    /// padded with holes on the right, which allows ragged boards.
+    ///
+    /// A `?` or `*` is a blank die that stands for any letter, and a bracketed
+    /// group such as `[aeiou]` is a blank restricted to those letters.
+    pub fn read<'a, I>(lines: I) -> Result<SimpleBoggleBoard, &'static str> where I: Iterator<Item=&'a str> {
+        let mut rows: Vec<Vec<SimpleBoggleCell>> = Vec::new();
+        let mut width = 0;
+
+        for line in lines {
+            let line = line.trim();
+            if line.is_empty() {
+                return Err("Empty line");
             }
+            let row = Self::read_row(line)?;
+            width = width.max(row.len());
+            rows.push(row);
 
-            let width = first.len();
-            let mut height = 1;
-            let mut cells: Vec<SimpleBoggleCell> = Vec::new();
-            
-            cells.extend(first.as_bytes().iter().map(|b| b - 'a' as u8));
+            // Padded out to the widest row so far, the board is only growing
+            match width.checked_mul(rows.len()) {
+                Some(size) if size <= MAX_CELLS => (),
+                _ => return Err("Board too large")
+            }
+        }
 
-            while let Some(line) = lines.next() {
-                let line = &line.trim().to_lowercase();
-                if !boggle_util::is_alpha(first) {
-                    return Err("Invalid chars");
-                }
+        if rows.is_empty() {
+            return Err("Empty iterator");
+        }
 
-                if line.len() != width {
-                    return Err("Invalid line length");
-                }
+        if rows.len() < 2 {
+            return Err("Board height must be >= 2");
+        }
 
-                cells.extend(line.as_bytes().iter().map(|b| b - 'a' as u8));
+        let height = rows.len();
+        if width < 2 {
+            return Err("Board width must be >= 2");
+        }
+        let mut cells: Vec<SimpleBoggleCell> = Vec::with_capacity(width * height);
+        for row in rows {
+            let pad = width - row.len();
+            cells.extend(row);
+            cells.extend(std::iter::repeat_n(HOLE, pad));
+        }
 
-                height += 1;
-            }
+        Ok(SimpleBoggleBoard {
+            width,
+            height,
+            cells: cells.into_boxed_slice()
+        })
+    }
 
-            if height > 1 {
-                Ok(SimpleBoggleBoard{
-                    width: width,
-                    height: height,
-                    cells: cells.into_boxed_slice()
-                })
-            } else {
-                Err("Board height must be >= 2")
+    /// Reads the cells of one row, in the syntax of SimpleBoggleBoard::read
+    pub fn read_row(line: &str) -> Result<Vec<SimpleBoggleCell>, &'static str> {
+        let mut row = Vec::with_capacity(line.len());
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '[' => {
+                    let mut letters = Vec::new();
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some(c) => match boggle_util::char_to_idx(c) {
+                                Some(v) => letters.push(v),
+                                None => return Err("Invalid chars"),
+                            },
+                            None => return Err("Unclosed letter set"),
+                        }
+                    }
+                    row.push(Cell::of(letters));
+                },
+                c if boggle_util::is_hole(c) => row.push(HOLE),
+                c if boggle_util::is_wildcard(c) => row.push(Cell::wildcard()),
+                c => match boggle_util::char_to_idx(c) {
+                    Some(v) => row.push(Cell::letter(v)),
+                    None => return Err("Invalid chars"),
+                },
             }
-        } else {
-            Err("Empty iterator")
         }
-        
-        
+        Ok(row)
     }
 
-    #[allow(dead_code)]
-    pub fn set(&mut self, i: usize, v: SimpleBoggleCell) -> () {
+    pub fn set(&mut self, i: usize, v: SimpleBoggleCell) {
         self.cells[i] = v;
     }
 
+    pub fn get(&self, i: usize) -> SimpleBoggleCell {
+        self.cells[i]
+    }
+
     // TODO REMOVE
-    pub fn iter(&self) -> std::slice::Iter<u8> {
+    pub fn iter(&self) -> std::slice::Iter<'_, SimpleBoggleCell> {
         self.cells.iter()
     }
 }
 
 
 
+/// Writes the board in the format read by SimpleBoggleBoard::read
+impl fmt::Display for SimpleBoggleBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.cells.chunks(self.width) {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
 impl /* BoggleBoard for*/ SimpleBoggleBoard {
     pub fn width(&self) -> usize {
         self.width
@@ -112,7 +175,7 @@ impl /* BoggleBoard for*/ SimpleBoggleBoard {
         self.height
     }
 
-    pub fn neighbors(&self, i: usize, v:SimpleBoggleCell) -> std::vec::IntoIter<usize> {
+    pub fn neighbors(&self, i: usize, v: u8) -> std::vec::IntoIter<usize> {
         let cands: Box<[(isize, isize)]> = match i {
             0 => Box::new([( 1isize,  0isize),
                            ( 1isize,  1isize),
@@ -173,20 +236,91 @@ impl /* BoggleBoard for*/ SimpleBoggleBoard {
 
         let v: Vec<usize> = cands
             .iter()
-            .map(|&coord| (coord.0 + self.width as isize * coord.1) as isize)
+            .map(|&coord| coord.0 + self.width as isize * coord.1)
             .map(|rel_idx| (i as isize + rel_idx) as usize)
-            .filter(|abs_idx| self.cells[*abs_idx] == v)
+            .filter(|abs_idx| self.cells[*abs_idx].contains(v))
             .collect();
         v.into_iter()
     }
 
-    pub fn any(&self, v:SimpleBoggleCell) -> std::vec::IntoIter<usize> {
+    pub fn any(&self, v: u8) -> std::vec::IntoIter<usize> {
         let v: Vec<usize> = self.cells
             .iter()
             .enumerate()
-            .filter(|&(_, &x)| x == v)
+            .filter(|&(_, x)| x.contains(v))
             .map(&|(i, &_)| i)
             .collect();
         v.into_iter()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::SimpleBoggleBoard;
+    use boggle::Cell;
+    use boggle::HOLE;
+
+    fn letters(board: &SimpleBoggleBoard) -> Vec<Cell> {
+        board.iter().cloned().collect()
+    }
+
+    #[test]
+    fn reads_rectangular_board() {
+        let board = SimpleBoggleBoard::read("ab\nCD".lines()).unwrap();
+
+        assert_eq!(board.width(), 2);
+        assert_eq!(board.height(), 2);
+        assert_eq!(letters(&board), vec![Cell::letter(0), Cell::letter(1), Cell::letter(2), Cell::letter(3)]);
+    }
+
+    #[test]
+    fn reads_holes_and_pads_ragged_rows() {
+        let board = SimpleBoggleBoard::read("a.c\n#b\nabc".lines()).unwrap();
+
+        assert_eq!(board.width(), 3);
+        assert_eq!(board.height(), 3);
+        assert_eq!(letters(&board), vec![Cell::letter(0), HOLE, Cell::letter(2),
+                                         HOLE, Cell::letter(1), HOLE,
+                                         Cell::letter(0), Cell::letter(1), Cell::letter(2)]);
+    }
+
+    #[test]
+    fn rejects_invalid_boards() {
+        assert!(SimpleBoggleBoard::read("ab\nc1".lines()).is_err());
+        assert!(SimpleBoggleBoard::read("ab\n\ncd".lines()).is_err());
+        assert!(SimpleBoggleBoard::read("abcd".lines()).is_err());
+        assert!(SimpleBoggleBoard::read("".lines()).is_err());
+        assert!(SimpleBoggleBoard::read("a\nb".lines()).is_err());
+        // Refused part way through, however many rows follow
+        assert!(SimpleBoggleBoard::read(std::iter::repeat("ab")).is_err());
+    }
+
+    #[test]
+    fn holes_are_never_yielded() {
+        let board = SimpleBoggleBoard::read("a.\n.a".lines()).unwrap();
+
+        assert_eq!(board.any(0).collect::<Vec<usize>>(), vec![0, 3]);
+        assert_eq!(board.neighbors(0, 0).collect::<Vec<usize>>(), vec![3]);
+        assert_eq!(board.neighbors(3, 0).collect::<Vec<usize>>(), vec![0]);
+    }
+
+    #[test]
+    fn reads_wildcards_and_letter_sets() {
+        let board = SimpleBoggleBoard::read("a?
+[bc]*".lines()).unwrap();
+
+        assert_eq!(letters(&board), vec![Cell::letter(0), Cell::wildcard(),
+                                         Cell::of(vec![1, 2]), Cell::wildcard()]);
+        assert_eq!(board.any(1).collect::<Vec<usize>>(), vec![1, 2, 3]);
+        assert_eq!(board.any(25).collect::<Vec<usize>>(), vec![1, 3]);
+        assert!(SimpleBoggleBoard::read("a[b\ncd".lines()).is_err());
+    }
+
+    #[test]
+    fn writes_what_it_reads() {
+        let text = "qa?.\n[aeiou]bcd\n";
+        let board = SimpleBoggleBoard::read(text.lines()).unwrap();
+
+        assert_eq!(board.to_string(), text);
+    }
+}
@@ -25,15 +25,84 @@
 
 pub const ALPHABET_SIZE: usize =  26;
 
+/// The text of each die face, 'q' is always followed by a 'u'
+const FACES: [&str; ALPHABET_SIZE] = [
+    "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m",
+    "n", "o", "p", "qu", "r", "s", "t", "u", "v", "w", "x", "y", "z",
+];
+
 pub fn ascii_byte_to_idx(b: u8) -> usize {
-    (b - 'a' as u8) as usize
+    (b - b'a') as usize
 }
 
 
+/*
+ * Spells a word the way the trie and boards do: lowercase, with each "qu"
+ * written as a single 'q'. Words that aren't alphabetic, or have a 'q' not
+ * followed by a 'u', can't be spelled with boggle dice and give None.
+ */
+pub fn normalize(word: &str) -> Option<String> {
+    if !is_alpha(word) {
+        return None;
+    }
+
+    let word = word.to_lowercase();
+    let mut s = String::with_capacity(word.len());
+    let mut iter = word.chars();
+    while let Some(c) = iter.next() {
+        s.push(c);
+        if c == 'q' && iter.next() != Some('u') {
+            // q not followed by u, or the word ends in q
+            return None;
+        }
+    }
+    Some(s)
+}
+
 pub fn is_alpha(s: &str) -> bool {
-    s.chars().all(|c| match c {
-        'a' ... 'z' => true,
-        'A' ... 'Z' => true,
-        _ => false
-    })
+    s.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+pub fn idx_to_face(v: u8) -> &'static str {
+    FACES[v as usize]
+}
+
+pub fn char_to_idx(c: char) -> Option<u8> {
+    match c {
+        'a' ..= 'z' => Some(c as u8 - b'a'),
+        'A' ..= 'Z' => Some(c as u8 - b'A'),
+        _ => None
+    }
+}
+
+/// Board text markers for a missing cell
+pub fn is_hole(c: char) -> bool {
+    c == '.' || c == '#'
+}
+
+/// Board text markers for a blank die
+pub fn is_wildcard(c: char) -> bool {
+    c == '?' || c == '*'
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::normalize;
+
+    #[test]
+    fn normalize_rewrites_qu() {
+        assert_eq!(normalize("Queen"), Some("qeen".to_owned()));
+        assert_eq!(normalize("QUIZ"), Some("qiz".to_owned()));
+        assert_eq!(normalize("cat"), Some("cat".to_owned()));
+    }
+
+    #[test]
+    fn normalize_rejects_unspellable_words() {
+        assert_eq!(normalize("qat"), None);
+        assert_eq!(normalize("QAT"), None);
+        assert_eq!(normalize("iraq"), None);
+        assert_eq!(normalize("don't"), None);
+        assert_eq!(normalize("naïve"), None);
+    }
 }
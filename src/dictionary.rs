@@ -0,0 +1,448 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Boggle rules state words must be at least three characters, also
+ * there is no Q face on any die, it's replaced with a Qu. As any
+ * word containing Q not followed by u is illegal, we filter them out
+ * an replace 'qu' with 'q' here, and repace 'q' with 'qu' when solving.
+ * (This seperates the game rules from trie and board represention)
+ */
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::path::Path;
+
+use bitset::BitSet32;
+use blocklist::Blocklist;
+use boggle_util;
+use trie::NodeType;
+use trie::Pattern;
+use trie::Trie;
+
+/// Shortest word allowed, counting Qu as two letters
+pub const MIN_LENGTH: usize = 3;
+
+/// What a wordlist tells us about a word, besides its spelling
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Meta {
+    /// How often the word appears in some corpus
+    pub frequency: Option<u64>,
+    /// Which of the sources of a composed dictionary hold the word
+    pub sources: BitSet32,
+}
+
+/// Why a wordlist line was left out of the dictionary
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rejection {
+    /// Something other than a count follows the word
+    InvalidFrequency,
+    TooShort,
+    NotAlphabetic,
+    /// A 'q' not followed by a 'u', which no die can spell
+    QWithoutU,
+    /// The same word as an earlier line, once spelled as the trie does
+    Duplicate,
+}
+
+impl Rejection {
+    pub const ALL: [Rejection; 5] = [
+        Rejection::InvalidFrequency,
+        Rejection::TooShort,
+        Rejection::NotAlphabetic,
+        Rejection::QWithoutU,
+        Rejection::Duplicate,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Rejection::InvalidFrequency => "invalid_frequency",
+            Rejection::TooShort => "too_short",
+            Rejection::NotAlphabetic => "not_alphabetic",
+            Rejection::QWithoutU => "q_without_u",
+            Rejection::Duplicate => "duplicate",
+        }
+    }
+}
+
+/*
+ * Splits a wordlist line into its word and frequency. Blank lines and lines
+ * starting with '#' give None. A word may be followed by its frequency,
+ * after a tab or spaces, as in `THE<TAB>23135851162`.
+ */
+
+pub fn parse_line(line: &str) -> Option<Result<(&str, Meta), Rejection>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let mut fields = line.split_whitespace();
+    let word = fields.next().unwrap();
+    match (fields.next().map(|f| f.parse()), fields.next()) {
+        (None, _) => Some(Ok((word, Meta::default()))),
+        (Some(Ok(frequency)), None) => Some(Ok((word, Meta { frequency: Some(frequency), ..Meta::default() }))),
+        _ => Some(Err(Rejection::InvalidFrequency))
+    }
+}
+
+/// Spells a word as the trie does, if the rules allow it
+pub fn spell(word: &str) -> Result<String, Rejection> {
+    if word.chars().count() < MIN_LENGTH {
+        return Err(Rejection::TooShort);
+    }
+    if !boggle_util::is_alpha(word) {
+        return Err(Rejection::NotAlphabetic);
+    }
+    boggle_util::normalize(word).ok_or(Rejection::QWithoutU)
+}
+
+/// A prefix spelled as in the trie, a trailing 'q' being the start of a "qu"
+fn spell_prefix(prefix: &str) -> Option<String> {
+    match prefix.strip_suffix(|c| c == 'q' || c == 'Q') {
+        Some(rest) => boggle_util::normalize(rest).map(|s| s + "q"),
+        None => boggle_util::normalize(prefix),
+    }
+}
+
+/// How a wordlist combines with the words composed before it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Op {
+    Union,
+    Intersect,
+    Subtract,
+    /// Adds no words, only lending its frequencies to words composed without one
+    Annotate,
+}
+
+/// Holds the root trie and hands out word ids in load order
+pub struct Dictionary {
+    trie: Trie,
+    len: usize,
+    /// Words taken out of the trie by a blocklist, their ids aren't reused
+    blocked: usize,
+    /// Keyed by word id
+    meta: Vec<Meta>,
+    /// Each word as stored in the trie, keyed by word id
+    words: Vec<String>,
+    /// Names of the wordlists composed into this one
+    sources: Vec<String>,
+}
+
+impl Dictionary {
+    pub fn new() -> Self {
+        Dictionary {
+            trie: Trie::new(),
+            len: 0,
+            blocked: 0,
+            meta: Vec::new(),
+            words: Vec::new(),
+            sources: Vec::new(),
+        }
+    }
+
+    /// Reads a wordlist, one word per line, see parse_line
+    pub fn read<I>(lines: I) -> Self where I: Iterator<Item=String> {
+        let mut dictionary = Self::new();
+        for line in lines {
+            if let Some(Ok((word, meta))) = parse_line(&line) {
+                dictionary.insert_with(word, meta);
+            }
+        }
+        dictionary
+    }
+
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let lines: io::Result<Vec<String>> = BufReader::new(file).lines().collect();
+        Ok(Self::read(lines?.into_iter()))
+    }
+
+    /// Adds a word, returns false if it breaks the rules or is already present
+    #[allow(dead_code)]
+    pub fn insert(&mut self, word: &str) -> bool {
+        self.insert_with(word, Meta::default())
+    }
+
+    pub fn insert_with(&mut self, word: &str, meta: Meta) -> bool {
+        match spell(word) {
+            Ok(s) => self.insert_normalized(s, meta),
+            Err(_) => false
+        }
+    }
+
+    fn insert_normalized(&mut self, s: String, meta: Meta) -> bool {
+        if let Some(NodeType::Word(_)) = self.trie.contains(&s) {
+            return false;
+        }
+        self.trie.insert(&s, self.len);
+        self.meta.push(meta);
+        self.words.push(s);
+        self.len += 1;
+        true
+    }
+
+    /*
+     * Combines wordlists in order, e.g. "twl06 plus slang minus offensive"
+     * is [(Union, twl06), (Union, slang), (Subtract, offensive)]. Words keep
+     * the order they were first added in, and remember every source that
+     * holds them, whatever the source's operation. The first frequency
+     * found for a word is kept, so a corpus can be annotated onto a list.
+     */
+
+    pub fn compose(sources: Vec<(Op, String, Dictionary)>) -> Result<Self, &'static str> {
+        if sources.len() > 32 {
+            return Err("At most 32 wordlists can be composed");
+        }
+
+        let mut order: Vec<&str> = Vec::new();
+        let mut kept: HashSet<&str> = HashSet::new();
+        for &(op, _, ref source) in sources.iter() {
+            match op {
+                Op::Union => for word in source.words() {
+                    if kept.insert(word) {
+                        order.push(word);
+                    }
+                },
+                Op::Intersect => kept.retain(|word| source.id(word).is_some()),
+                Op::Subtract => for word in source.words() {
+                    kept.remove(word);
+                },
+                Op::Annotate => (),
+            }
+        }
+
+        let mut dictionary = Self::new();
+        for word in order.into_iter().filter(|word| kept.contains(word)) {
+            let mut meta = Meta::default();
+            for (k, (_, _, source)) in sources.iter().enumerate() {
+                if let Some(id) = source.id(word) {
+                    meta.sources.add(k as u32);
+                    meta.frequency = meta.frequency.or(source.meta[id].frequency);
+                }
+            }
+            dictionary.insert_normalized(word.to_owned(), meta);
+        }
+        dictionary.sources = sources.into_iter().map(|(_, name, _)| name).collect();
+        Ok(dictionary)
+    }
+
+    /*
+     * Takes every word on the blocklist out of the trie, returning how many.
+     * The nodes stay as prefixes, so longer words through them are still
+     * found: blocking "ass" keeps "assess".
+     */
+
+    pub fn block(&mut self, blocklist: &Blocklist) -> usize {
+        let mut blocked = 0;
+        for s in self.words.iter() {
+            if blocklist.contains(&s.replace('q', "qu")) && self.trie.remove(s).is_some() {
+                blocked += 1;
+            }
+        }
+        self.blocked += blocked;
+        blocked
+    }
+
+    /// Every word still in the trie, as stored there
+    fn words(&self) -> impl Iterator<Item=&str> {
+        self.words.iter().map(|s| s.as_str()).filter(move |s| self.id(s).is_some())
+    }
+
+    /// The id of a word spelled as stored in the trie
+    fn id(&self, s: &str) -> Option<usize> {
+        match self.trie.contains(s) {
+            Some(NodeType::Word(id)) => Some(id),
+            _ => None
+        }
+    }
+
+    /// The id of a word spelled as a player would, if it's in the dictionary
+    pub fn lookup(&self, word: &str) -> Option<usize> {
+        boggle_util::normalize(word).and_then(|s| self.id(&s))
+    }
+
+    /*
+     * Words starting with a prefix, spelled in full with their ids. A prefix
+     * ending in 'q' stands for one ending in "qu", as no word can continue
+     * a 'q' any other way.
+     */
+    pub fn prefixed<'a>(&'a self, prefix: &str) -> impl Iterator<Item=(String, usize)> + 'a {
+        spell_prefix(prefix)
+            .and_then(|p| self.trie.prefixed(&p))
+            .into_iter()
+            .flatten()
+            .map(|(s, id)| (s.replace('q', "qu"), id))
+    }
+
+    /// How many words start with a prefix, without spelling them out
+    pub fn count_prefixed(&self, prefix: &str) -> usize {
+        spell_prefix(prefix).and_then(|p| self.trie.get(&p)).map_or(0, |node| node.count())
+    }
+
+    /*
+     * Words matching a crossword style pattern, '?' or '.' being any one
+     * letter and '*' any run of them. Letters are counted as spelled, so
+     * "..qu.." is a six letter word.
+     */
+    pub fn matching<'a>(&'a self, pattern: &str) -> Result<impl Iterator<Item=(String, usize)> + 'a, &'static str> {
+        let pattern = Pattern::parse(pattern)?;
+        Ok(self.trie.matching(pattern).map(|(s, id)| (s.replace('q', "qu"), id)))
+    }
+
+    pub fn meta(&self, id: usize) -> Meta {
+        self.meta[id]
+    }
+
+    /// The index of a composed source by name, for testing Meta::sources
+    pub fn source(&self, name: &str) -> Option<usize> {
+        self.sources.iter().position(|s| s == name)
+    }
+
+    pub fn trie(&self) -> &Trie {
+        &self.trie
+    }
+
+    pub fn len(&self) -> usize {
+        self.len - self.blocked
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use blocklist::Blocklist;
+    use super::Dictionary;
+    use super::Op;
+
+    fn dictionary(words: &[&str]) -> Dictionary {
+        Dictionary::read(words.iter().map(|w| w.to_string()))
+    }
+
+    #[test]
+    fn follows_the_rules() {
+        let dict = dictionary(&["an", "ant", "queen", "qat", "it's", "Quiz"]);
+
+        assert_eq!(dict.len(), 3);
+        assert_eq!(dict.lookup("an"), None);
+        assert_eq!(dict.lookup("ant"), Some(0));
+        assert_eq!(dict.lookup("QUEEN"), Some(1));
+        assert_eq!(dict.lookup("quiz"), Some(2));
+        assert_eq!(dict.lookup("qat"), None);
+    }
+
+    #[test]
+    fn queries_spell_qu_in_full() {
+        let dict = dictionary(&["quit", "quite", "quiet", "equip", "aqua", "squid", "quad", "duit", "suit", "liquid"]);
+
+        let words: Vec<String> = dict.prefixed("Q").map(|(s, _)| s).collect();
+        assert_eq!(words, vec!["quad", "quiet", "quit", "quite"]);
+        assert_eq!(dict.count_prefixed("qui"), 3);
+        assert_eq!(dict.count_prefixed("q"), 4);
+        assert_eq!(dict.count_prefixed("qa"), 0);
+
+        // Pattern positions count letters as spelled, a "qu" taking two
+        let matching = |pattern: &str| -> Vec<String> { dict.matching(pattern).unwrap().map(|(s, _)| s).collect() };
+        assert_eq!(matching("?qu??"), vec!["equip", "squid"]);
+        assert_eq!(matching("..qu.."), vec!["liquid"]);
+        assert_eq!(matching("?uit"), vec!["duit", "quit", "suit"]);
+        assert_eq!(matching("Q?IT"), vec!["quit"]);
+        assert_eq!(matching("...."), vec!["aqua", "duit", "quad", "quit", "suit"]);
+        assert!(matching("...").is_empty());
+        assert!(matching("q").is_empty());
+        assert!(dict.matching("q-").is_err());
+    }
+
+    #[test]
+    fn reads_frequencies_and_comments() {
+        let dict = dictionary(&["# counts", "THE\t23135851162", "", "quiz  12", "ant", "bee\tmany", "cat 1 2", "ANT\t5"]);
+
+        assert_eq!(dict.len(), 3);
+        assert_eq!(dict.meta(dict.lookup("the").unwrap()).frequency, Some(23135851162));
+        assert_eq!(dict.meta(dict.lookup("quiz").unwrap()).frequency, Some(12));
+        assert_eq!(dict.meta(dict.lookup("ant").unwrap()).frequency, None);
+        assert_eq!(dict.lookup("bee"), None);
+        assert_eq!(dict.lookup("cat"), None);
+    }
+
+    #[test]
+    fn composes_wordlists() {
+        let twl = dictionary(&["ant\t10", "bee", "cat", "quiz"]);
+        let slang = dictionary(&["yeet", "ant\t20", "bee"]);
+        let rude = dictionary(&["cat"]);
+        let common = dictionary(&["quiz", "ant", "yeet", "dog"]);
+        let corpus = dictionary(&["ant\t30", "yeet\t40", "the\t50"]);
+
+        let dict = Dictionary::compose(vec![
+            (Op::Union, "twl".to_owned(), twl),
+            (Op::Union, "slang".to_owned(), slang),
+            (Op::Subtract, "rude".to_owned(), rude),
+            (Op::Intersect, "common".to_owned(), common),
+            (Op::Annotate, "corpus".to_owned(), corpus),
+        ]).unwrap();
+
+        assert_eq!(dict.len(), 3);
+        assert_eq!(dict.lookup("the"), None);
+        assert_eq!(dict.meta(2).frequency, Some(40));
+        assert_eq!((dict.lookup("ant"), dict.lookup("quiz"), dict.lookup("yeet")), (Some(0), Some(1), Some(2)));
+        assert_eq!(dict.lookup("cat"), None);
+        assert_eq!(dict.lookup("bee"), None);
+
+        let ant = dict.meta(0);
+        assert_eq!(ant.frequency, Some(10));
+        let (twl, slang, common) = (dict.source("twl").unwrap(), dict.source("slang").unwrap(), dict.source("common").unwrap());
+        assert!(ant.sources.get(twl as u32) && ant.sources.get(slang as u32) && ant.sources.get(common as u32));
+        assert!(!dict.meta(1).sources.get(slang as u32));
+        assert_eq!(dict.meta(2).sources.cardinality(), 3);
+    }
+
+    #[test]
+    fn blocked_words_leave_their_prefix() {
+        let mut dict = dictionary(&["ass", "assess", "quiz", "quizzes", "bee"]);
+        let blocklist = Blocklist::read(["ASS", "quiz*"].iter().map(|l| l.to_string()));
+
+        assert_eq!(dict.block(&blocklist), 3);
+        assert_eq!(dict.len(), 2);
+        assert_eq!(dict.lookup("ass"), None);
+        assert_eq!(dict.lookup("assess"), Some(1));
+        assert_eq!(dict.lookup("quizzes"), None);
+
+        let composed = Dictionary::compose(vec![(Op::Union, "blocked".to_owned(), dict)]).unwrap();
+        assert_eq!(composed.len(), 2);
+        assert_eq!(composed.lookup("bee"), Some(1));
+    }
+
+    #[test]
+    fn ids_are_unique() {
+        let dict = dictionary(&["ant", "ANT", "bee"]);
+
+        assert_eq!(dict.len(), 2);
+        assert_eq!(dict.lookup("bee"), Some(1));
+        assert_eq!(dict.lookup(""), None);
+    }
+}
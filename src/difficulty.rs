@@ -0,0 +1,154 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Rates how hard a board is for a casual player
+ *
+ * Each word on the board gets a chance of being found, from how common it
+ * is (its frequency in the dictionary, such as one annotated with
+ * wordlists/count_1w100k.txt) and how long it is: everyone
+ * spots "the", few spot "tenet". The board's rating is the points a casual
+ * player can expect to score, the sum of each word's points times its
+ * chance. Boards with few points to expect are hard, whatever their total.
+ */
+
+use boggle::RadixBoggleBoard;
+use dictionary::Dictionary;
+use game::points;
+use solver::Found;
+use solver::Solution;
+
+/// Words counted fewer times than this are never found casually
+const RARE: f64 = 5.0;
+/// Words counted more times than this are always found, given their length
+const COMMON: f64 = 9.0;
+/// The chance of spotting a word drops by this for every letter past three
+const LENGTH_DECAY: f64 = 0.8;
+
+/// Expected points at or above which a board is easy, and below which it's hard
+/// (About the top and bottom thirds of boards rolled from the classic dice)
+const EASY: f64 = 35.0;
+const HARD: f64 = 22.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Label {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Label {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Label::Easy => "easy",
+            Label::Medium => "medium",
+            Label::Hard => "hard",
+        }
+    }
+}
+
+/// A word on the board and how likely a casual player is to find it
+pub struct Rated {
+    pub word: String,
+    pub count: u64,
+    pub chance: f64,
+    pub points: u32,
+}
+
+pub struct Rating {
+    pub words: Vec<Rated>,
+    /// Points for every word on the board
+    pub possible: u32,
+    /// Points a casual player can expect to score
+    pub expected: f64,
+    pub label: Label,
+}
+
+/// The chance of a casual player finding a word of this length seen this often
+pub fn chance(count: u64, len: usize) -> f64 {
+    if count == 0 {
+        return 0.0;
+    }
+    let commonness = ((count as f64).log10() - RARE) / (COMMON - RARE);
+    commonness.clamp(0.0, 1.0) * LENGTH_DECAY.powi(len.saturating_sub(3) as i32)
+}
+
+pub fn rate(dictionary: &Dictionary, board: &RadixBoggleBoard, solution: &Solution) -> Rating {
+    let mut words: Vec<Rated> = solution.iter().map(|found: &Found| {
+        let count = dictionary.meta(found.id).frequency.unwrap_or(0);
+        Rated {
+            chance: chance(count, found.len()),
+            points: points(found.len()),
+            word: found.render(|pos| !board.get(pos).is_letter()),
+            count,
+        }
+    }).collect();
+    words.sort_by(|a, b| b.chance.partial_cmp(&a.chance).unwrap().then_with(|| a.word.cmp(&b.word)));
+
+    let possible = words.iter().map(|w| w.points).sum();
+    let expected = words.iter().map(|w| w.points as f64 * w.chance).sum();
+    let label = if expected >= EASY {
+        Label::Easy
+    } else if expected < HARD {
+        Label::Hard
+    } else {
+        Label::Medium
+    };
+
+    Rating { words, possible, expected, label }
+}
+
+
+#[cfg(test)]
+mod test {
+    use dictionary::Dictionary;
+    use fixture;
+    use solver;
+    use super::*;
+
+    #[test]
+    fn common_short_words_are_likely() {
+        assert_eq!(chance(0, 3), 0.0);
+        assert_eq!(chance(10_000, 3), 0.0);
+        assert_eq!(chance(1_000_000_000, 3), 1.0);
+        assert_eq!(chance(1_000_000_000, 5), 0.8 * 0.8);
+        assert!((chance(10_000_000, 4) - 0.5 * 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rates_boards() {
+        let counts = ["\t100000000", "\t1000000", "", "\t10000000", ""];
+        let dictionary = Dictionary::read(fixture::WORDS.iter().zip(counts.iter()).map(|(w, c)| format!("{}{}", w, c)));
+        let board = fixture::radix("qet\nena\n?.s");
+        let rating = rate(&dictionary, &board, &solver::solve_radix(dictionary.trie(), &board));
+
+        let words: Vec<&str> = rating.words.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(words, vec!["queen", "neat", "tan", "ante"]);
+        assert_eq!(rating.possible, 5);
+        // queen 2 * 0.75 * 0.64, neat 1 * 0.5 * 0.8, tan 1 * 0.25
+        assert!((rating.expected - (0.96 + 0.4 + 0.25)).abs() < 1e-9);
+        assert_eq!(rating.label, Label::Hard);
+    }
+}
@@ -0,0 +1,154 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Draws boards in the terminal
+ *
+ * Every die takes two columns so "Qu" lines up with the other faces. When
+ * a path is given, its cells are numbered in the order the word is spelled,
+ * and with color on they're also shown in reverse video.
+ */
+
+use boggle::*;
+use boggle_util;
+use solver;
+
+const HIGHLIGHT: &str = "\x1b[7m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Options {
+    /// Number the rows and columns, as in the [row, column] of a path
+    pub coordinates: bool,
+    /// Highlight the path with ANSI escapes
+    pub color: bool,
+}
+
+/// The board as a grid of dice, with the cells of path highlighted
+pub fn grid(board: &SimpleBoggleBoard, path: &[usize], options: &Options) -> String {
+    draw(board, path, |i| face(board.get(i)), options)
+}
+
+/*
+ * Draws the board with the path of a word on it, or None if the word can't
+ * be spelled on the board. Blanks on the path show the letter they stand
+ * for, in lowercase to tell them from real dice.
+ */
+
+pub fn explain(board: &SimpleBoggleBoard, word: &str, options: &Options) -> Option<String> {
+    let normalized = boggle_util::normalize(word)?;
+    let path = solver::find_word_radix(&RadixBoggleBoard::from(board), word)?;
+
+    let letters: Vec<u8> = normalized.bytes().map(|b| b - b'a').collect();
+    Some(draw(board, &path, |i| {
+        let cell = board.get(i);
+        match path.iter().position(|&p| p == i) {
+            Some(k) if !cell.is_letter() => boggle_util::idx_to_face(letters[k]).to_owned(),
+            _ => face(cell)
+        }
+    }, options))
+}
+
+/// The face printed on a die: "A", "Qu", "?" for a blank, blank for a hole
+pub fn face(cell: Cell) -> String {
+    if cell.is_letter() {
+        let v = cell.letters().next().unwrap() as u8;
+        let face = boggle_util::idx_to_face(v);
+        face[..1].to_uppercase() + &face[1..]
+    } else if cell == HOLE {
+        " ".to_owned()
+    } else {
+        "?".to_owned()
+    }
+}
+
+fn draw<F>(board: &SimpleBoggleBoard, path: &[usize], label: F, options: &Options) -> String where F: Fn(usize) -> String {
+    // Wide enough for the face and its step on the path
+    let steps = if path.is_empty() { 0 } else { digits(path.len()) };
+    let width = if options.coordinates { (2 + steps).max(digits(board.width() - 1)) } else { 2 + steps };
+    let margin = digits(board.height() - 1);
+
+    let mut s = String::new();
+    if options.coordinates {
+        s.push_str(&" ".repeat(margin + 1));
+        for x in 0..board.width() {
+            s.push_str(&format!(" {:<w$}", x, w = width));
+        }
+        s.push('\n');
+    }
+
+    for y in 0..board.height() {
+        if options.coordinates {
+            s.push_str(&format!("{:>w$} ", y, w = margin));
+        }
+        for x in 0..board.width() {
+            let i = y * board.width() + x;
+            let cell = match path.iter().position(|&p| p == i) {
+                Some(k) => format!("{:<2}{:>w$}", label(i), k + 1, w = width - 2),
+                None => format!("{:<w$}", label(i), w = width),
+            };
+            if options.color && path.contains(&i) {
+                s.push_str(&format!(" {}{}{}", HIGHLIGHT, cell, RESET));
+            } else {
+                s.push_str(&format!(" {}", cell));
+            }
+        }
+        s.push('\n');
+    }
+    s
+}
+
+fn digits(n: usize) -> usize {
+    n.to_string().len()
+}
+
+
+#[cfg(test)]
+mod test {
+    use boggle::SimpleBoggleBoard;
+    use super::*;
+
+    fn board() -> SimpleBoggleBoard {
+        SimpleBoggleBoard::read("qet\nena\n?.s".lines()).unwrap()
+    }
+
+    #[test]
+    fn aligns_faces() {
+        assert_eq!(grid(&board(), &[], &Options::default()), " Qu E  T \n E  N  A \n ?     S \n");
+        assert_eq!(grid(&board(), &[], &Options { coordinates: true, color: false }),
+                   "   0  1  2 \n0  Qu E  T \n1  E  N  A \n2  ?     S \n");
+    }
+
+    #[test]
+    fn numbers_the_path() {
+        let plain = Options::default();
+        assert_eq!(explain(&board(), "zen", &plain).unwrap(), " Qu  E   T  \n E 2 N 3 A  \n z 1     S  \n");
+
+        let color = Options { coordinates: false, color: true };
+        assert_eq!(explain(&board(), "zen", &color).unwrap().matches(HIGHLIGHT).count(), 3);
+        assert!(explain(&board(), "seat", &color).is_none());
+        assert!(explain(&board(), "q", &color).is_none());
+    }
+}
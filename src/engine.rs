@@ -0,0 +1,265 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * A long running engine speaking line delimited JSON, in the spirit of UCI
+ *
+ * Each input line is one command object, and each command gets exactly one
+ * response line. The dictionary and board stay loaded between commands, so
+ * a front end only pays for building the trie once.
+ *
+ *   {"cmd": "load_dictionary", "path": "wordlists/enable1.txt"}
+ *   {"cmd": "load_dictionary", "words": ["tan", "neat"]}
+ *   {"cmd": "set_board", "board": ["qet", "ena", "..s"]}
+ *   {"cmd": "solve"}
+ *   {"cmd": "validate", "words": ["tan", "seat"]}
+ *   {"cmd": "score", "players": [["tan", "neat"], ["tan"]]}
+ *   {"cmd": "quit"}
+ *
+ * Responses carry "ok", plus "error" on failure. An "id" on a command is
+ * echoed back on its response.
+ */
+
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+
+use api;
+use boggle::*;
+use dictionary::Dictionary;
+use game::*;
+use json;
+use json::Json;
+
+pub struct Engine {
+    dictionary: Option<Dictionary>,
+    board: Option<RadixBoggleBoard>,
+}
+
+impl Engine {
+    pub fn new(dictionary: Option<Dictionary>) -> Self {
+        Engine { dictionary, board: None }
+    }
+
+    /// Answers commands from input until it ends or a quit command
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let (response, quit) = self.execute(&line);
+            writeln!(output, "{}", response)?;
+            output.flush()?;
+
+            if quit {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs one command line, returning the response and whether to stop
+    pub fn execute(&mut self, line: &str) -> (Json, bool) {
+        let command = match Json::parse(line) {
+            Ok(command) => command,
+            Err(e) => return (respond(None, Err(e.to_owned())), false)
+        };
+
+        let id = command.get("id");
+        let cmd = command.get("cmd").and_then(|c| c.as_str());
+        let result = match cmd {
+            Some("load_dictionary") => self.load_dictionary(&command),
+            Some("set_board") => self.set_board(&command),
+            Some("solve") => self.ready().map(|(dictionary, board)| api::solve(dictionary, board)),
+            Some("validate") => self.ready().and_then(|(dictionary, board)| {
+                api::validate(dictionary, board, command.get("words")).map_err(|e| e.to_owned())
+            }),
+            Some("score") => self.score(&command),
+            Some("quit") => Ok(json::object(vec![])),
+            Some(_) => Err("Unknown command".to_owned()),
+            None => Err("Expected \"cmd\" to be a string".to_owned()),
+        };
+
+        (respond(id, result), cmd == Some("quit"))
+    }
+
+    fn load_dictionary(&mut self, command: &Json) -> Result<Json, String> {
+        let dictionary = if let Some(path) = command.get("path").and_then(|p| p.as_str()) {
+            Dictionary::open(path).map_err(|e| e.to_string())?
+        } else if let Some(words) = command.get("words").and_then(|w| w.as_array()) {
+            let words: Option<Vec<&str>> = words.iter().map(|w| w.as_str()).collect();
+            let words = words.ok_or("Expected \"words\" to be strings")?;
+            Dictionary::read(words.into_iter().map(|w| w.to_owned()))
+        } else {
+            return Err("Expected a \"path\" or \"words\"".to_owned());
+        };
+
+        let len = dictionary.len();
+        self.dictionary = Some(dictionary);
+        Ok(json::object(vec![("words", len.into())]))
+    }
+
+    fn set_board(&mut self, command: &Json) -> Result<Json, String> {
+        let board = api::board_from_json(command.get("board"))?;
+        self.board = Some(RadixBoggleBoard::from(&board));
+        Ok(json::object(vec![
+            ("width", board.width().into()),
+            ("height", board.height().into()),
+        ]))
+    }
+
+    fn score(&self, command: &Json) -> Result<Json, String> {
+        let (dictionary, board) = self.ready()?;
+        let players = command.get("players").and_then(|p| p.as_array()).ok_or("Expected \"players\" to be an array")?;
+        let players: Option<Vec<Vec<&str>>> = players
+            .iter()
+            .map(|words| words.as_array().and_then(|words| words.iter().map(|w| w.as_str()).collect()))
+            .collect();
+        let players = players.ok_or("Expected \"players\" to be arrays of strings")?;
+
+        let result = score_round(dictionary, board, &players);
+        let players: Vec<Json> = result.players.iter().map(|player| {
+            let words: Vec<Json> = player.words.iter().map(|scored| {
+                json::object(vec![
+                    ("word", scored.word.as_str().into()),
+                    ("verdict", scored.verdict.name().into()),
+                    ("shared", scored.shared.into()),
+                    ("points", scored.points.into()),
+                ])
+            }).collect();
+            json::object(vec![
+                ("score", player.score.into()),
+                ("words", Json::Array(words)),
+            ])
+        }).collect();
+
+        Ok(json::object(vec![
+            ("players", Json::Array(players)),
+            ("missed", result.missed.into()),
+            ("possible", result.possible.into()),
+        ]))
+    }
+
+    fn ready(&self) -> Result<(&Dictionary, &RadixBoggleBoard), String> {
+        match (&self.dictionary, &self.board) {
+            (Some(dictionary), Some(board)) => Ok((dictionary, board)),
+            (None, _) => Err("No dictionary loaded".to_owned()),
+            (_, None) => Err("No board set".to_owned()),
+        }
+    }
+}
+
+/// Wraps a result in a response, with "ok" first and the command's id echoed
+fn respond(id: Option<&Json>, result: Result<Json, String>) -> Json {
+    let mut members = match result {
+        Ok(Json::Object(members)) => members,
+        Ok(value) => vec![("result".to_owned(), value)],
+        Err(e) => vec![("error".to_owned(), e.into())],
+    };
+
+    let ok = !members.iter().any(|(k, _)| k == "error");
+    members.insert(0, ("ok".to_owned(), ok.into()));
+    if let Some(id) = id {
+        members.insert(0, ("id".to_owned(), id.clone()));
+    }
+    Json::Object(members)
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use dictionary::Dictionary;
+    use fixture;
+    use json::Json;
+    use super::Engine;
+
+    /// Feeds the lines to a new engine, returning its responses
+    fn run(dictionary: Option<Dictionary>, lines: &[&str]) -> Vec<Json> {
+        let mut output = Vec::new();
+        Engine::new(dictionary).run(Cursor::new(lines.join("\n")), &mut output).unwrap();
+        String::from_utf8(output).unwrap().lines().map(|line| Json::parse(line).unwrap()).collect()
+    }
+
+    #[test]
+    fn answers_each_command() {
+        let words = Json::Array(fixture::WORDS.iter().map(|&w| w.into()).collect());
+        let responses = run(None, &[
+            &format!(r#"{{"cmd": "load_dictionary", "words": {}}}"#, words),
+            r#"{"cmd": "set_board", "board": ["qet", "ena", "..s"], "id": 7}"#,
+            "",
+            r#"{"cmd": "solve"}"#,
+            r#"{"cmd": "validate", "words": ["tan", "seat"]}"#,
+            r#"{"cmd": "score", "players": [["tan", "queen"], ["tan"]]}"#,
+            r#"{"cmd": "quit"}"#,
+            r#"{"cmd": "solve"}"#,
+        ]);
+
+        assert_eq!(responses.len(), 6);
+        assert_eq!(responses[0].to_string(), r#"{"ok":true,"words":5}"#);
+        assert_eq!(responses[1].to_string(), r#"{"id":7,"ok":true,"width":3,"height":3}"#);
+        assert_eq!(responses[2].get("count").unwrap().to_string(), "4");
+        assert_eq!(responses[3].get("score").unwrap().to_string(), "1");
+        assert_eq!(responses[4].get("players").unwrap().to_string(),
+                   r#"[{"score":2,"words":[{"word":"tan","verdict":"accepted","shared":true,"points":0},{"word":"queen","verdict":"accepted","shared":false,"points":2}]},{"score":0,"words":[{"word":"tan","verdict":"accepted","shared":true,"points":0}]}]"#);
+        assert_eq!(responses[5].to_string(), r#"{"ok":true}"#);
+    }
+
+    #[test]
+    fn reports_errors_and_keeps_going() {
+        let dictionary = Dictionary::read(["tan"].iter().map(|w| w.to_string()));
+        let responses = run(Some(dictionary), &[
+            "{",
+            r#"{"cmd": "solve"}"#,
+            r#"{"cmd": "set_board", "board": "ab1\ncd"}"#,
+            r#"{"cmd": "dance"}"#,
+            r#"{"cmd": "set_board", "board": "tan\n..."}"#,
+            r#"{"cmd": "solve"}"#,
+        ]);
+
+        let errors: Vec<String> = responses[..4].iter().map(|r| r.get("error").unwrap().to_string()).collect();
+        assert_eq!(errors, vec![r#""Expected a key""#, r#""No board set""#, r#""Invalid chars""#, r#""Unknown command""#]);
+        assert_eq!(responses[5].get("count").unwrap().to_string(), "1");
+    }
+
+    #[test]
+    fn refuses_boards_it_cannot_hold() {
+        let dictionary = Dictionary::read(["tan"].iter().map(|w| w.to_string()));
+        let responses = run(Some(dictionary), &[
+            r#"{"cmd": "set_board", "board": ["a", "b"]}"#,
+            r#"{"cmd": "set_board", "board": "1x2-custom-ab"}"#,
+            r#"{"cmd": "set_board", "board": "100000x100000-custom-a"}"#,
+            r#"{"cmd": "set_board", "board": "tan/..."}"#,
+            r#"{"cmd": "solve"}"#,
+        ]);
+
+        assert_eq!(responses.len(), 5);
+        assert!(responses[..3].iter().all(|r| r.get("ok").unwrap().to_string() == "false"));
+        assert_eq!(responses[4].get("count").unwrap().to_string(), "1");
+    }
+}
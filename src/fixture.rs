@@ -0,0 +1,53 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * The dictionary and board most tests share
+ *
+ *   q e t
+ *   e n a      queen, tan, ante and neat are on it, seat isn't
+ *   . . s
+ *
+ * Tests wanting blanks or dead dice swap out the bottom row.
+ */
+
+use boggle::*;
+use dictionary::Dictionary;
+
+pub const WORDS: [&str; 5] = ["queen", "tan", "ante", "neat", "seat"];
+
+pub const BOARD: &str = "qet\nena\n..s";
+
+pub fn dictionary() -> Dictionary {
+    Dictionary::read(WORDS.iter().map(|w| w.to_string()))
+}
+
+pub fn board(rows: &str) -> SimpleBoggleBoard {
+    SimpleBoggleBoard::read(rows.lines()).unwrap()
+}
+
+pub fn radix(rows: &str) -> RadixBoggleBoard {
+    RadixBoggleBoard::from(&board(rows))
+}
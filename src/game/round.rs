@@ -0,0 +1,147 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Scores one round of several players on the same board. As in real Boggle,
+ * a word found by more than one player scores nothing for any of them.
+ */
+
+use std::collections::HashMap;
+
+use boggle::RadixBoggleBoard;
+use dictionary::Dictionary;
+use solver;
+
+use super::Validator;
+use super::points;
+use super::Verdict;
+
+/// How one submitted word fared
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Scored {
+    pub word: String,
+    pub verdict: Verdict,
+    /// Another player found the word too, so it's worth nothing
+    pub shared: bool,
+    pub points: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct PlayerResult {
+    pub words: Vec<Scored>,
+    pub score: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct RoundResult {
+    pub players: Vec<PlayerResult>,
+    /// Words on the board that nobody found
+    pub missed: Vec<String>,
+    /// Points for every word on the board
+    pub possible: u32,
+}
+
+pub fn score_round<S: AsRef<str>>(dictionary: &Dictionary, board: &RadixBoggleBoard, submissions: &[Vec<S>]) -> RoundResult {
+    // Validate every list on its own, counting how many players found each word
+    let mut finders: HashMap<usize, usize> = HashMap::new();
+    let mut verdicts: Vec<Vec<(String, Verdict, Option<usize>)>> = Vec::with_capacity(submissions.len());
+
+    for words in submissions {
+        let mut validator = Validator::new(dictionary, board);
+        let mut player = Vec::with_capacity(words.len());
+
+        for word in words {
+            let word = word.as_ref().trim();
+            let verdict = validator.validate(word);
+            let id = match verdict {
+                Verdict::Accepted(..) => dictionary.lookup(word),
+                _ => None
+            };
+
+            if let Some(id) = id {
+                *finders.entry(id).or_insert(0) += 1;
+            }
+            player.push((word.to_lowercase(), verdict, id));
+        }
+        verdicts.push(player);
+    }
+
+    let players = verdicts.into_iter().map(|player| {
+        let words: Vec<Scored> = player.into_iter().map(|(word, verdict, id)| {
+            let shared = id.is_some_and(|id| finders[&id] > 1);
+            let points = match verdict {
+                Verdict::Accepted(_, points) if !shared => points,
+                _ => 0
+            };
+            Scored { word, verdict, shared, points }
+        }).collect();
+
+        let score = words.iter().map(|w| w.points).sum();
+        PlayerResult { words, score }
+    }).collect();
+
+    let solution = solver::solve_radix(dictionary.trie(), board);
+    let possible = solution.iter().map(|found| points(found.len())).sum();
+    let missed = solution
+        .iter()
+        .filter(|found| !finders.contains_key(&found.id))
+        .map(|found| found.render(|pos| !board.get(pos).is_letter()))
+        .collect();
+
+    RoundResult { players, missed, possible }
+}
+
+
+#[cfg(test)]
+mod test {
+    use fixture;
+    use super::score_round;
+
+    #[test]
+    fn shared_words_cancel() {
+        let dict = fixture::dictionary();
+        let board = fixture::radix(fixture::BOARD);
+
+        let result = score_round(&dict, &board, &[
+            vec!["queen", "tan", "tan", "seat"],
+            vec!["TAN", "ante"],
+        ]);
+
+        let alice = &result.players[0];
+        assert_eq!(alice.score, 2);
+        assert_eq!(alice.words.iter().map(|w| w.points).collect::<Vec<u32>>(), vec![2, 0, 0, 0]);
+        assert!(alice.words[1].shared);
+        assert!(!alice.words[2].shared);
+
+        let bob = &result.players[1];
+        assert_eq!(bob.score, 1);
+        assert!(bob.words[0].shared);
+        assert_eq!(bob.words[0].word, "tan");
+
+        assert_eq!(result.missed, vec!["neat".to_owned()]);
+        assert_eq!(result.possible, 5);
+    }
+}
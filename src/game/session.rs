@@ -0,0 +1,284 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * A timed round of Boggle as a state machine
+ *
+ * A session starts out Ready, runs for a fixed time once started, and is
+ * Finished when the clock runs out (or the round is ended early). Words are
+ * only accepted while it runs. Time comes from an injectable clock, so front
+ * ends use the system clock and tests can step time by hand. Likewise the
+ * board is rolled from an injected random number generator, which tests seed.
+ */
+
+use std::mem;
+use std::time::Duration;
+use std::time::Instant;
+
+use rand::Rng;
+
+use bitset::BitSet;
+use boggle::*;
+use dictionary::Dictionary;
+
+use super::RoundResult;
+use super::Validator;
+use super::Verdict;
+use super::score_round;
+
+/// Three minutes, as in the box rules
+pub const DEFAULT_DURATION: Duration = Duration::from_secs(180);
+
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum State {
+    Ready,
+    Running,
+    Finished,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SessionError {
+    NotStarted,
+    AlreadyStarted,
+    NotFinished,
+    TimeUp,
+    UnknownPlayer,
+    /// Boards are at least 2x2 and at most MAX_CELLS
+    InvalidSize,
+}
+
+pub struct Session<'a, C: Clock> {
+    dictionary: &'a Dictionary,
+    board: SimpleBoggleBoard,
+    radix: RadixBoggleBoard,
+    clock: C,
+    duration: Duration,
+    started: Option<Instant>,
+    /// Set when the round is ended before the clock runs out
+    ended: bool,
+    /// Ids of the words each player has had accepted
+    found: Vec<BitSet>,
+    submissions: Vec<Vec<String>>,
+}
+
+impl<'a, C: Clock> Session<'a, C> {
+    /// A session on a board rolled from the dice for its size
+    pub fn new<R: Rng>(dictionary: &'a Dictionary, width: usize, height: usize, players: usize, clock: C, rng: &mut R) -> Result<Self, SessionError> {
+        match width.checked_mul(height) {
+            Some(cells) if width >= 2 && height >= 2 && cells <= MAX_CELLS => (),
+            _ => return Err(SessionError::InvalidSize)
+        }
+        Ok(Self::with_board(dictionary, dice::generate(width, height, rng), players, clock))
+    }
+
+    /// A session on a board chosen by hand
+    pub fn with_board(dictionary: &'a Dictionary, board: SimpleBoggleBoard, players: usize, clock: C) -> Self {
+        Session {
+            dictionary,
+            radix: RadixBoggleBoard::from(&board),
+            board,
+            clock,
+            duration: DEFAULT_DURATION,
+            started: None,
+            ended: false,
+            found: (0..players).map(|_| BitSet::new()).collect(),
+            submissions: vec![Vec::new(); players],
+        }
+    }
+
+    pub fn board(&self) -> &SimpleBoggleBoard {
+        &self.board
+    }
+
+    pub fn set_duration(&mut self, duration: Duration) -> Result<(), SessionError> {
+        match self.state() {
+            State::Ready => {
+                self.duration = duration;
+                Ok(())
+            },
+            _ => Err(SessionError::AlreadyStarted)
+        }
+    }
+
+    pub fn state(&self) -> State {
+        match self.started {
+            None => State::Ready,
+            Some(_) if self.ended || self.remaining() == Duration::from_secs(0) => State::Finished,
+            Some(_) => State::Running,
+        }
+    }
+
+    /// Time left on the clock, the full duration before the round starts
+    pub fn remaining(&self) -> Duration {
+        match self.started {
+            Some(started) => self.duration.saturating_sub(self.clock.now().saturating_duration_since(started)),
+            None => self.duration,
+        }
+    }
+
+    pub fn start(&mut self) -> Result<(), SessionError> {
+        match self.state() {
+            State::Ready => {
+                self.started = Some(self.clock.now());
+                Ok(())
+            },
+            _ => Err(SessionError::AlreadyStarted)
+        }
+    }
+
+    /// Ends the round before the clock runs out
+    pub fn finish(&mut self) -> Result<(), SessionError> {
+        match self.state() {
+            State::Ready => Err(SessionError::NotStarted),
+            _ => {
+                self.ended = true;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn submit(&mut self, player: usize, word: &str) -> Result<Verdict, SessionError> {
+        match self.state() {
+            State::Ready => return Err(SessionError::NotStarted),
+            State::Finished => return Err(SessionError::TimeUp),
+            State::Running => ()
+        }
+
+        match self.found.get_mut(player) {
+            Some(found) => {
+                let mut validator = Validator::resume(self.dictionary, &self.radix, mem::take(found));
+                let verdict = validator.validate(word);
+                *found = validator.into_found();
+                self.submissions[player].push(word.to_owned());
+                Ok(verdict)
+            },
+            None => Err(SessionError::UnknownPlayer)
+        }
+    }
+
+    pub fn results(&self) -> Result<RoundResult, SessionError> {
+        match self.state() {
+            State::Finished => Ok(score_round(self.dictionary, &self.radix, &self.submissions)),
+            _ => Err(SessionError::NotFinished)
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use rand::SeedableRng;
+    use rand::XorShiftRng;
+
+    use dictionary::Dictionary;
+    use fixture;
+    use game::Verdict;
+    use super::*;
+
+    struct ManualClock {
+        now: Cell<Instant>,
+    }
+
+    impl ManualClock {
+        fn advance(&self, secs: u64) {
+            self.now.set(self.now.get() + Duration::from_secs(secs));
+        }
+    }
+
+    impl Clock for &ManualClock {
+        fn now(&self) -> Instant {
+            self.now.get()
+        }
+    }
+
+    #[test]
+    fn runs_a_round() {
+        let dict = fixture::dictionary();
+        let board = fixture::board(fixture::BOARD);
+        let clock = ManualClock { now: Cell::new(Instant::now()) };
+        let mut session = Session::with_board(&dict, board, 2, &clock);
+
+        assert_eq!(session.state(), State::Ready);
+        assert_eq!(session.submit(0, "tan"), Err(SessionError::NotStarted));
+        assert_eq!(session.set_duration(Duration::from_secs(60)), Ok(()));
+        assert_eq!(session.start(), Ok(()));
+        assert_eq!(session.start(), Err(SessionError::AlreadyStarted));
+
+        clock.advance(20);
+        assert_eq!(session.state(), State::Running);
+        assert_eq!(session.remaining(), Duration::from_secs(40));
+        assert_eq!(session.submit(0, "tan"), Ok(Verdict::Accepted(vec![2, 5, 4], 1)));
+        assert_eq!(session.submit(0, "tan"), Ok(Verdict::AlreadyFound));
+        assert_eq!(session.submit(1, "tan"), Ok(Verdict::Accepted(vec![2, 5, 4], 1)));
+        assert_eq!(session.submit(1, "queen"), Ok(Verdict::Accepted(vec![0, 1, 3, 4], 2)));
+        assert_eq!(session.submit(2, "queen"), Err(SessionError::UnknownPlayer));
+        assert_eq!(session.results().err(), Some(SessionError::NotFinished));
+
+        clock.advance(40);
+        assert_eq!(session.state(), State::Finished);
+        assert_eq!(session.submit(0, "ante"), Err(SessionError::TimeUp));
+
+        let results = session.results().unwrap();
+        assert_eq!(results.players[0].score, 0);
+        assert_eq!(results.players[1].score, 2);
+        assert_eq!(results.missed, vec!["ante".to_owned(), "neat".to_owned()]);
+    }
+
+    #[test]
+    fn rolls_its_board_and_can_finish_early() {
+        let dict = Dictionary::new();
+        let mut session = Session::new(&dict, 5, 4, 1, SystemClock, &mut XorShiftRng::from_seed([1, 2, 3, 4])).unwrap();
+        let again = Session::new(&dict, 5, 4, 1, SystemClock, &mut XorShiftRng::from_seed([1, 2, 3, 4])).unwrap();
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        assert_eq!(Session::new(&dict, 1, 4, 1, SystemClock, &mut rng).err(), Some(SessionError::InvalidSize));
+        assert_eq!(Session::new(&dict, 0, 0, 1, SystemClock, &mut rng).err(), Some(SessionError::InvalidSize));
+        assert_eq!(Session::new(&dict, usize::MAX, 2, 1, SystemClock, &mut rng).err(), Some(SessionError::InvalidSize));
+
+        assert_eq!((session.board().width(), session.board().height()), (5, 4));
+        assert_eq!(session.board().to_string(), again.board().to_string());
+
+        assert_eq!(session.finish(), Err(SessionError::NotStarted));
+        session.start().unwrap();
+        session.finish().unwrap();
+        assert_eq!(session.state(), State::Finished);
+        assert!(session.results().is_ok());
+    }
+}
@@ -0,0 +1,141 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+use bitset::BitSet;
+use boggle::RadixBoggleBoard;
+use boggle_util;
+use dictionary;
+use dictionary::Dictionary;
+use solver;
+
+use super::points;
+
+/// Why a submitted word was or wasn't accepted
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    /// The path spelling the word, and the points it's worth
+    Accepted(Vec<usize>, u32),
+    TooShort,
+    NotInDictionary,
+    NotOnBoard,
+    AlreadyFound,
+    InvalidCharacters,
+}
+
+impl Verdict {
+    /// A stable name for the verdict, for machine readable output
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Verdict::Accepted(..) => "accepted",
+            Verdict::TooShort => "too_short",
+            Verdict::NotInDictionary => "not_in_dictionary",
+            Verdict::NotOnBoard => "not_on_board",
+            Verdict::AlreadyFound => "already_found",
+            Verdict::InvalidCharacters => "invalid_characters",
+        }
+    }
+}
+
+/// Checks one player's submissions against a board, remembering what they found
+pub struct Validator<'a> {
+    dictionary: &'a Dictionary,
+    board: &'a RadixBoggleBoard,
+    /// Ids of the words already accepted
+    found: BitSet,
+}
+
+impl<'a> Validator<'a> {
+    pub fn new(dictionary: &'a Dictionary, board: &'a RadixBoggleBoard) -> Self {
+        Validator {
+            dictionary,
+            board,
+            found: BitSet::new(),
+        }
+    }
+
+    /// Picks up where a validator on the same board left off, given what it had found
+    pub fn resume(dictionary: &'a Dictionary, board: &'a RadixBoggleBoard, found: BitSet) -> Self {
+        Validator { dictionary, board, found }
+    }
+
+    /// Ids of the words accepted so far, to resume from
+    pub fn into_found(self) -> BitSet {
+        self.found
+    }
+
+    pub fn validate(&mut self, word: &str) -> Verdict {
+        let word = word.trim();
+
+        if !boggle_util::is_alpha(word) || word.is_empty() {
+            return Verdict::InvalidCharacters;
+        }
+
+        if word.len() < dictionary::MIN_LENGTH {
+            return Verdict::TooShort;
+        }
+
+        let id = match self.dictionary.lookup(word) {
+            Some(id) => id,
+            None => return Verdict::NotInDictionary
+        };
+
+        if self.found.get(id) {
+            return Verdict::AlreadyFound;
+        }
+
+        match solver::find_word_radix(self.board, word) {
+            Some(path) => {
+                self.found.add(id);
+                Verdict::Accepted(path, points(word.len()))
+            },
+            None => Verdict::NotOnBoard
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use fixture;
+    use super::Validator;
+    use super::Verdict;
+
+    #[test]
+    fn explains_every_verdict() {
+        let dict = fixture::dictionary();
+        let board = fixture::radix(fixture::BOARD);
+        let mut validator = Validator::new(&dict, &board);
+
+        assert_eq!(validator.validate("Queen"), Verdict::Accepted(vec![0, 1, 3, 4], 2));
+        assert_eq!(validator.validate("queen"), Verdict::AlreadyFound);
+        assert_eq!(validator.validate("tan"), Verdict::Accepted(vec![2, 5, 4], 1));
+        assert_eq!(validator.validate("seat"), Verdict::NotOnBoard);
+        assert_eq!(validator.validate("tea"), Verdict::NotInDictionary);
+        assert_eq!(validator.validate("qat"), Verdict::NotInDictionary);
+        assert_eq!(validator.validate("at"), Verdict::TooShort);
+        assert_eq!(validator.validate("an te"), Verdict::InvalidCharacters);
+        assert_eq!(validator.validate(""), Verdict::InvalidCharacters);
+    }
+}
@@ -0,0 +1,167 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Counts how each cell of a board is used by the words on it
+ *
+ * For every cell: how many words pass through it, how many points those
+ * words are worth, and how many words start on it. A word counts once for
+ * each cell some path spelling it passes through or starts on, however many
+ * such paths there are. Dice no word passes through are dead, and a good
+ * sign the dice need rebalancing.
+ */
+
+use api::path_to_json;
+use boggle::*;
+use game::points;
+use json;
+use json::Json;
+use solver::Solution;
+use solver::each_path_radix;
+
+pub struct Heatmap {
+    pub width: usize,
+    pub height: usize,
+    pub words: Vec<u32>,
+    pub points: Vec<u32>,
+    pub starts: Vec<u32>,
+    /// Cells holding a die that no word passes through
+    pub dead: Vec<usize>,
+}
+
+impl Heatmap {
+    pub fn of(board: &RadixBoggleBoard, solution: &Solution) -> Self {
+        let cells = board.width() * board.height();
+        let mut words = vec![0; cells];
+        let mut points = vec![0; cells];
+        let mut starts = vec![0; cells];
+
+        let mut on = Vec::new();
+        let mut from = Vec::new();
+        for found in solution.iter() {
+            on.clear();
+            from.clear();
+            each_path_radix(board, &found.word, |path| {
+                on.extend_from_slice(path);
+                from.push(path[0]);
+            });
+            on.sort_unstable();
+            on.dedup();
+            from.sort_unstable();
+            from.dedup();
+
+            let value = self::points(found.len());
+            for &i in on.iter() {
+                words[i] += 1;
+                points[i] += value;
+            }
+            for &i in from.iter() {
+                starts[i] += 1;
+            }
+        }
+
+        let dead = (0..cells).filter(|&i| words[i] == 0 && board.get(i) != HOLE).collect();
+        Heatmap { width: board.width(), height: board.height(), words, points, starts, dead }
+    }
+
+    /// The cells starting the most words, most first, leaving out those starting none
+    pub fn top_starts(&self, n: usize) -> Vec<(usize, u32)> {
+        let mut starts: Vec<(usize, u32)> = self.starts.iter().cloned().enumerate().filter(|&(_, s)| s > 0).collect();
+        starts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        starts.truncate(n);
+        starts
+    }
+
+    /// The values as rows of right aligned numbers
+    pub fn grid(&self, values: &[u32]) -> String {
+        let width = values.iter().map(|v| v.to_string().len()).max().unwrap_or(1);
+        let mut s = String::new();
+        for row in values.chunks(self.width) {
+            for v in row {
+                s.push_str(&format!(" {:>w$}", v, w = width));
+            }
+            s.push('\n');
+        }
+        s
+    }
+
+    pub fn to_json(&self) -> Json {
+        let rows = |values: &[u32]| -> Json {
+            Json::Array(values.chunks(self.width).map(|row| row.to_vec().into()).collect())
+        };
+        let top: Vec<Json> = self.top_starts(10).into_iter().map(|(i, n)| json::object(vec![
+            ("cell", vec![i / self.width, i % self.width].into()),
+            ("words", n.into()),
+        ])).collect();
+
+        json::object(vec![
+            ("width", self.width.into()),
+            ("height", self.height.into()),
+            ("words", rows(&self.words)),
+            ("points", rows(&self.points)),
+            ("starts", rows(&self.starts)),
+            ("top_starts", Json::Array(top)),
+            ("dead", path_to_json(&self.dead, self.width)),
+        ])
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use boggle::*;
+    use dictionary::Dictionary;
+    use fixture;
+    use solver;
+    use super::Heatmap;
+
+    #[test]
+    fn counts_words_points_and_starts() {
+        let dictionary = fixture::dictionary();
+        let board = fixture::radix("qet\nena\nx.s");
+        let heatmap = Heatmap::of(&board, &solver::solve_radix(dictionary.trie(), &board));
+
+        // queen 0 1 3 4, tan 2 5 4, ante 5 4 2 1, neat 4 1 5 2
+        assert_eq!(heatmap.words, vec![1, 3, 3, 1, 4, 3, 0, 0, 0]);
+        assert_eq!(heatmap.points, vec![2, 4, 3, 2, 5, 3, 0, 0, 0]);
+        assert_eq!(heatmap.starts, vec![1, 0, 1, 0, 1, 1, 0, 0, 0]);
+        assert_eq!(heatmap.dead, vec![6, 8]);
+        assert_eq!(heatmap.top_starts(2), vec![(0, 1), (2, 1)]);
+        assert_eq!(heatmap.grid(&heatmap.words), " 1 3 3\n 1 4 3\n 0 0 0\n");
+        assert_eq!(heatmap.to_json().get("dead").unwrap().to_string(), "[[2,0],[2,2]]");
+    }
+
+    #[test]
+    fn counts_every_path_of_a_word() {
+        let dictionary = Dictionary::read(["tab"].iter().map(|w| w.to_string()));
+        let board = RadixBoggleBoard::from(&SimpleBoggleBoard::read("tab\n..b".lines()).unwrap());
+        let heatmap = Heatmap::of(&board, &solver::solve_radix(dictionary.trie(), &board));
+
+        // Either b ends the word, the solver only recording the first
+        assert_eq!(heatmap.words, vec![1, 1, 1, 0, 0, 1]);
+        assert_eq!(heatmap.starts, vec![1, 0, 0, 0, 0, 0]);
+        assert!(heatmap.dead.is_empty());
+    }
+}
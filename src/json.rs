@@ -0,0 +1,394 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Just enough JSON for the server and engine protocols: a value type,
+ * a parser and a serializer (via Display).
+ */
+
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Keys are kept in insertion order
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn parse(s: &str) -> Result<Json, &'static str> {
+        let mut chars = Input { chars: s.chars().peekable(), depth: 0 };
+        let value = parse_value(&mut chars)?;
+        skip_whitespace(&mut chars);
+        match chars.next() {
+            None => Ok(value),
+            Some(_) => Err("Trailing characters")
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match *self {
+            Json::Object(ref members) => members.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Json::String(ref s) => Some(s),
+            _ => None
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match *self {
+            Json::Array(ref values) => Some(values),
+            _ => None
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Json::Number(n) if n >= 0.0 && n.fract() == 0.0 => Some(n as u64),
+            _ => None
+        }
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json {
+        Json::Bool(b)
+    }
+}
+
+impl From<u32> for Json {
+    fn from(n: u32) -> Json {
+        Json::Number(n as f64)
+    }
+}
+
+impl From<usize> for Json {
+    fn from(n: usize) -> Json {
+        Json::Number(n as f64)
+    }
+}
+
+impl From<f64> for Json {
+    fn from(n: f64) -> Json {
+        Json::Number(n)
+    }
+}
+
+impl<'a> From<&'a str> for Json {
+    fn from(s: &'a str) -> Json {
+        Json::String(s.to_owned())
+    }
+}
+
+impl From<String> for Json {
+    fn from(s: String) -> Json {
+        Json::String(s)
+    }
+}
+
+impl<T: Into<Json>> From<Vec<T>> for Json {
+    fn from(values: Vec<T>) -> Json {
+        Json::Array(values.into_iter().map(|v| v.into()).collect())
+    }
+}
+
+/// Builds an object from key value pairs, keeping their order
+pub fn object(members: Vec<(&str, Json)>) -> Json {
+    Json::Object(members.into_iter().map(|(k, v)| (k.to_owned(), v)).collect())
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Number(n) if !n.is_finite() => write!(f, "null"),
+            Json::Number(n) if n.fract() == 0.0 && n.abs() < 1e15 => write!(f, "{}", n as i64),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(ref s) => write_string(f, s),
+            Json::Array(ref values) => {
+                write!(f, "[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                write!(f, "]")
+            },
+            Json::Object(ref members) => {
+                write!(f, "{{")?;
+                for (i, (k, v)) in members.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, k)?;
+                    write!(f, ":{}", v)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+//==============================================================================
+
+/// Arrays and objects nested deeper than this are refused, as parsing them recurses
+const MAX_DEPTH: usize = 128;
+
+struct Input<'a> {
+    chars: Peekable<Chars<'a>>,
+    /// Arrays and objects open around the current character
+    depth: usize,
+}
+
+impl<'a> Input<'a> {
+    fn peek(&mut self) -> Option<&char> {
+        self.chars.peek()
+    }
+}
+
+impl<'a> Iterator for Input<'a> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        self.chars.next()
+    }
+}
+
+fn skip_whitespace(chars: &mut Input) {
+    while let Some(&c) = chars.peek() {
+        if c == ' ' || c == '\t' || c == '\n' || c == '\r' {
+            chars.next();
+        } else {
+            break;
+        }
+    }
+}
+
+fn parse_value(chars: &mut Input) -> Result<Json, &'static str> {
+    skip_whitespace(chars);
+    match chars.peek() {
+        Some(&'n') => parse_literal(chars, "null", Json::Null),
+        Some(&'t') => parse_literal(chars, "true", Json::Bool(true)),
+        Some(&'f') => parse_literal(chars, "false", Json::Bool(false)),
+        Some(&'"') => parse_string(chars).map(Json::String),
+        Some(&'[') => nested(chars, parse_array),
+        Some(&'{') => nested(chars, parse_object),
+        Some(&c) if c == '-' || c.is_ascii_digit() => parse_number(chars),
+        Some(_) => Err("Unexpected character"),
+        None => Err("Unexpected end of input")
+    }
+}
+
+fn nested(chars: &mut Input, parse: fn(&mut Input) -> Result<Json, &'static str>) -> Result<Json, &'static str> {
+    if chars.depth >= MAX_DEPTH {
+        return Err("Too deeply nested");
+    }
+    chars.depth += 1;
+    let value = parse(chars);
+    chars.depth -= 1;
+    value
+}
+
+fn parse_literal(chars: &mut Input, literal: &str, value: Json) -> Result<Json, &'static str> {
+    for expected in literal.chars() {
+        if chars.next() != Some(expected) {
+            return Err("Invalid literal");
+        }
+    }
+    Ok(value)
+}
+
+fn parse_number(chars: &mut Input) -> Result<Json, &'static str> {
+    let mut s = String::new();
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' || c == 'e' || c == 'E' {
+            s.push(c);
+            chars.next();
+        } else {
+            break;
+        }
+    }
+    s.parse().map(Json::Number).map_err(|_| "Invalid number")
+}
+
+fn parse_string(chars: &mut Input) -> Result<String, &'static str> {
+    chars.next();
+    let mut s = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(s),
+            Some('\\') => match chars.next() {
+                Some('"') => s.push('"'),
+                Some('\\') => s.push('\\'),
+                Some('/') => s.push('/'),
+                Some('b') => s.push('\u{8}'),
+                Some('f') => s.push('\u{c}'),
+                Some('n') => s.push('\n'),
+                Some('r') => s.push('\r'),
+                Some('t') => s.push('\t'),
+                Some('u') => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    let code = u32::from_str_radix(&hex, 16).map_err(|_| "Invalid escape")?;
+                    // Surrogate pairs aren't needed by any of our protocols
+                    s.push(::std::char::from_u32(code).unwrap_or('\u{fffd}'));
+                },
+                _ => return Err("Invalid escape")
+            },
+            Some(c) => s.push(c),
+            None => return Err("Unterminated string")
+        }
+    }
+}
+
+fn parse_array(chars: &mut Input) -> Result<Json, &'static str> {
+    chars.next();
+    let mut values = Vec::new();
+    skip_whitespace(chars);
+    if chars.peek() == Some(&']') {
+        chars.next();
+        return Ok(Json::Array(values));
+    }
+    loop {
+        values.push(parse_value(chars)?);
+        skip_whitespace(chars);
+        match chars.next() {
+            Some(',') => continue,
+            Some(']') => return Ok(Json::Array(values)),
+            _ => return Err("Expected ',' or ']'")
+        }
+    }
+}
+
+fn parse_object(chars: &mut Input) -> Result<Json, &'static str> {
+    chars.next();
+    let mut members = Vec::new();
+    skip_whitespace(chars);
+    if chars.peek() == Some(&'}') {
+        chars.next();
+        return Ok(Json::Object(members));
+    }
+    loop {
+        skip_whitespace(chars);
+        if chars.peek() != Some(&'"') {
+            return Err("Expected a key");
+        }
+        let key = parse_string(chars)?;
+        skip_whitespace(chars);
+        if chars.next() != Some(':') {
+            return Err("Expected ':'");
+        }
+        let value = parse_value(chars)?;
+        members.push((key, value));
+        skip_whitespace(chars);
+        match chars.next() {
+            Some(',') => continue,
+            Some('}') => return Ok(Json::Object(members)),
+            _ => return Err("Expected ',' or '}'")
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::Json;
+    use super::object;
+
+    #[test]
+    fn parses_values() {
+        let json = Json::parse(r#" {"a": [1, -2.5, true, null], "b": "x\"A\n", "c": {}} "#).unwrap();
+
+        assert_eq!(json.get("a"), Some(&Json::Array(vec![
+            Json::Number(1.0), Json::Number(-2.5), Json::Bool(true), Json::Null
+        ])));
+        assert_eq!(json.get("b").and_then(|b| b.as_str()), Some("x\"A\n"));
+        assert_eq!(json.get("c"), Some(&Json::Object(vec![])));
+        assert_eq!(json.get("d"), None);
+    }
+
+    #[test]
+    fn rejects_invalid_json() {
+        assert!(Json::parse("").is_err());
+        assert!(Json::parse("{").is_err());
+        assert!(Json::parse("[1,]").is_err());
+        assert!(Json::parse("{\"a\" 1}").is_err());
+        assert!(Json::parse("\"abc").is_err());
+        assert!(Json::parse("tru").is_err());
+        assert!(Json::parse("1 2").is_err());
+    }
+
+    #[test]
+    fn limits_nesting() {
+        let nested = |depth: usize| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(Json::parse(&nested(128)).is_ok());
+        assert_eq!(Json::parse(&nested(129)), Err("Too deeply nested"));
+        assert_eq!(Json::parse(&"[{\"a\":".repeat(100)), Err("Too deeply nested"));
+        assert_eq!(Json::parse(&"[".repeat(60000)), Err("Too deeply nested"));
+    }
+
+    #[test]
+    fn serializes_values() {
+        let json = object(vec![
+            ("word", "q\"u".into()),
+            ("path", vec![vec![0usize, 1], vec![1, 2]].into()),
+            ("points", 11u32.into()),
+            ("ratio", 0.5.into()),
+            ("ok", true.into()),
+        ]);
+
+        assert_eq!(json.to_string(), r#"{"word":"q\"u","path":[[0,1],[1,2]],"points":11,"ratio":0.5,"ok":true}"#);
+        assert_eq!(Json::parse(&json.to_string()).unwrap(), json);
+    }
+}
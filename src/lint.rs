@@ -0,0 +1,200 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Reports what loading a wordlist would keep and drop, and why
+ *
+ * Mirrors Dictionary::read line for line, counting each rejection with a
+ * few sample lines, and pairing up lines that turn out to be the same word
+ * once spelled as the trie does ("Queen" and "queen"). The words kept are
+ * summed up as a histogram of their lengths and the letters they use.
+ */
+
+use std::collections::HashMap;
+
+use boggle_util;
+use dictionary;
+use dictionary::Rejection;
+use json;
+use json::Json;
+
+/// Lines kept as samples for each kind of problem
+pub const SAMPLES: usize = 5;
+
+pub struct Report {
+    pub lines: usize,
+    /// Blank and comment lines
+    pub skipped: usize,
+    pub accepted: usize,
+    /// Count and sample lines for each reason, in the order of Rejection::ALL
+    pub rejected: Vec<(Rejection, usize, Vec<String>)>,
+    /// Sample lines spelling an earlier word, with that earlier line
+    pub duplicates: Vec<(String, String)>,
+    /// Words kept of each length, Qu counting as two letters
+    pub lengths: Vec<usize>,
+    /// Uses of each letter over the words kept, a Qu being a 'q' and a 'u'
+    pub letters: [usize; boggle_util::ALPHABET_SIZE],
+}
+
+pub fn check<I>(lines: I) -> Report where I: Iterator<Item=String> {
+    let mut report = Report {
+        lines: 0,
+        skipped: 0,
+        accepted: 0,
+        rejected: Rejection::ALL.iter().map(|&r| (r, 0, Vec::new())).collect(),
+        duplicates: Vec::new(),
+        lengths: Vec::new(),
+        letters: [0; boggle_util::ALPHABET_SIZE],
+    };
+    let mut seen: HashMap<String, String> = HashMap::new();
+
+    for line in lines {
+        report.lines += 1;
+        let spelled = match dictionary::parse_line(&line) {
+            None => {
+                report.skipped += 1;
+                continue;
+            },
+            Some(entry) => entry.and_then(|(word, _)| dictionary::spell(word).map(|s| (word.to_owned(), s)))
+        };
+
+        let rejection = match spelled {
+            Ok((word, s)) => match seen.get(&s) {
+                Some(first) => {
+                    if report.duplicates.len() < SAMPLES {
+                        report.duplicates.push((line.trim().to_owned(), first.clone()));
+                    }
+                    Rejection::Duplicate
+                },
+                None => {
+                    accept(&mut report, &word);
+                    seen.insert(s, line.trim().to_owned());
+                    continue;
+                }
+            },
+            Err(rejection) => rejection
+        };
+
+        let entry = report.rejected.iter_mut().find(|&&mut (r, _, _)| r == rejection).unwrap();
+        entry.1 += 1;
+        if entry.2.len() < SAMPLES {
+            entry.2.push(line.trim().to_owned());
+        }
+    }
+    report
+}
+
+fn accept(report: &mut Report, word: &str) {
+    report.accepted += 1;
+
+    let len = word.len();
+    if report.lengths.len() <= len {
+        report.lengths.resize(len + 1, 0);
+    }
+    report.lengths[len] += 1;
+
+    for c in word.chars() {
+        if let Some(v) = boggle_util::char_to_idx(c) {
+            report.letters[v as usize] += 1;
+        }
+    }
+}
+
+impl Report {
+    pub fn to_json(&self) -> Json {
+        let rejected: Vec<(&str, Json)> = self.rejected.iter().map(|(rejection, count, samples)| {
+            (rejection.name(), json::object(vec![
+                ("count", (*count).into()),
+                ("samples", samples.clone().into()),
+            ]))
+        }).collect();
+        let duplicates: Vec<Json> = self.duplicates.iter().map(|(line, first)| json::object(vec![
+            ("line", line.as_str().into()),
+            ("first", first.as_str().into()),
+        ])).collect();
+        let letters: Vec<(&str, Json)> = (0..boggle_util::ALPHABET_SIZE)
+            .map(|v| (&"abcdefghijklmnopqrstuvwxyz"[v..v + 1], self.letters[v].into()))
+            .collect();
+
+        json::object(vec![
+            ("lines", self.lines.into()),
+            ("skipped", self.skipped.into()),
+            ("accepted", self.accepted.into()),
+            ("rejected", json::object(rejected)),
+            ("duplicates", Json::Array(duplicates)),
+            ("lengths", self.lengths.clone().into()),
+            ("letters", json::object(letters)),
+        ])
+    }
+}
+
+impl ::std::fmt::Display for Report {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        writeln!(f, "{} lines: {} accepted, {} blank or comments", self.lines, self.accepted, self.skipped)?;
+
+        for (rejection, count, samples) in self.rejected.iter().filter(|r| r.1 > 0) {
+            writeln!(f, "{} {}: {}", count, rejection.name(), samples.join(", "))?;
+        }
+        for (line, first) in self.duplicates.iter() {
+            writeln!(f, "  {:?} spells the same word as {:?}", line, first)?;
+        }
+
+        writeln!(f, "\nLengths:")?;
+        let most = self.lengths.iter().cloned().max().unwrap_or(0).max(1);
+        for (len, &count) in self.lengths.iter().enumerate().filter(|&(_, &c)| c > 0) {
+            writeln!(f, "{:>3} {:>8} {}", len, count, "#".repeat((count * 50).div_ceil(most)))?;
+        }
+
+        writeln!(f, "\nLetters:")?;
+        let total = self.letters.iter().sum::<usize>().max(1);
+        for (v, &count) in self.letters.iter().enumerate() {
+            writeln!(f, "  {} {:>9} {:5.2}%", (b'a' + v as u8) as char, count, count as f64 * 100.0 / total as f64)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use dictionary::Rejection;
+    use super::check;
+
+    #[test]
+    fn counts_every_rejection() {
+        let lines = ["# list", "ant", "", "an", "it's", "qat", "QAT", "Iraq", "Queen", "QUEEN", "bee many", "ant", "quiz\t7"];
+        let report = check(lines.iter().map(|l| l.to_string()));
+
+        assert_eq!((report.lines, report.skipped, report.accepted), (13, 2, 3));
+        let counts: Vec<(Rejection, usize)> = report.rejected.iter().map(|r| (r.0, r.1)).collect();
+        assert_eq!(counts, vec![(Rejection::InvalidFrequency, 1), (Rejection::TooShort, 1), (Rejection::NotAlphabetic, 1),
+                                (Rejection::QWithoutU, 3), (Rejection::Duplicate, 2)]);
+        assert_eq!(report.rejected[3].2, vec!["qat".to_owned(), "QAT".to_owned(), "Iraq".to_owned()]);
+        assert_eq!(report.duplicates, vec![("QUEEN".to_owned(), "Queen".to_owned()), ("ant".to_owned(), "ant".to_owned())]);
+
+        assert_eq!(report.lengths, vec![0, 0, 0, 1, 1, 1]);
+        assert_eq!((report.letters[16], report.letters[20], report.letters[4]), (2, 2, 2));
+    }
+}
@@ -23,275 +23,532 @@
  * POSSIBILITY OF SUCH DAMAGE.
  */
 
+mod anagram;
+mod api;
+mod batch;
+mod bee;
+mod blocklist;
 mod boggle_util;
 mod bitset;
 mod trie;
 mod boggle;
+mod dictionary;
+mod difficulty;
+mod display;
+mod engine;
+#[cfg(test)]
+mod fixture;
+mod game;
+mod heatmap;
+mod json;
+mod lint;
+mod play;
+mod report;
+mod server;
+mod solver;
+mod svg;
 
 extern crate rayon;
+extern crate rand;
 
-use trie::NodeType;
-use trie::Trie;
 use boggle::*;
-use bitset::BitSet;
+use dictionary::Dictionary;
+use solver::*;
 
-use rayon::prelude::*;
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.first().map(|s| s.as_str()) {
+        Some("play") => run_play(&args[1..]),
+        Some("serve") => run_serve(&args[1..]),
+        Some("engine") => run_engine(&args[1..]),
+        Some("batch") => run_batch(&args[1..]),
+        Some("code") => run_code(&args[1..]),
+        Some("svg") => run_svg(&args[1..]),
+        Some("show") => run_show(&args[1..]),
+        Some("heatmap") => run_heatmap(&args[1..]),
+        Some("difficulty") => run_difficulty(&args[1..]),
+        Some("dict-check") => run_dict_check(&args[1..]),
+        Some("words") => run_words(&args[1..]),
+        Some("anagram") => run_anagram(&args[1..]),
+        Some("bee") => run_bee(&args[1..]),
+        _ => run_solve(&args),
+    }
+}
+
+/// The value following a `--name` flag
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|arg| arg == name)
+        .and_then(|i| args.get(i + 1))
+        .map(|s| s.as_str())
+}
 
 /*
- * Recursively search for dictionary words on the boggle board
- *
- * By using a prefix trie we prune words that cant be found
- * (because their prefixes aren't on the board)
+ * Loads --dict, or composes several wordlists when --union, --intersect or
+ * --subtract follow it, applied in the order given. A --freq corpus adds no
+ * words, only frequencies for words without one. Words on --blocklist are
+ * then taken out.
  */
 
-#[allow(dead_code)]
-fn solve(root: &Trie, board: &SimpleBoggleBoard) {
-    let mut word = Vec::with_capacity(64);
-    let mut path = Vec::with_capacity(64);
-    let mut words = BitSet::new();
-    for (trie, i) in root.iter() {
-        word.push(i);
-        for pos in board.any(i) {
-            path.push(pos);
-            descend(trie, board, &mut word, &mut path, &mut words);
-            path.pop();
-        }
-        word.pop();
-    }
+fn load_dictionary(args: &[String]) -> Dictionary {
+    open_dictionary(args, true)
 }
 
-#[allow(dead_code)]
-fn par_solve(root: &Trie, board: &SimpleBoggleBoard) {
+/// Loads the dictionary, logging its build to stderr only when asked
+fn open_dictionary(args: &[String], log: bool) -> Dictionary {
+    use std::io::Write;
+    use std::time::Instant;
+    use dictionary::Op;
 
-    let forest: Vec<(&Trie, u8)> = root.iter().collect();
-    forest.par_iter().for_each(|&(ref trie, i)| {
-        let mut word = Vec::with_capacity(64);
-        let mut path = Vec::with_capacity(64);
-        let mut words = BitSet::new();
+    let start = Instant::now();
+
+    let mut lists: Vec<(Op, &str)> = Vec::new();
+    for (flag, path) in args.iter().zip(args.iter().skip(1)) {
+        match flag.as_str() {
+            "--dict" | "--union" => lists.push((Op::Union, path)),
+            "--intersect" => lists.push((Op::Intersect, path)),
+            "--subtract" => lists.push((Op::Subtract, path)),
+            "--freq" => lists.push((Op::Annotate, path)),
+            _ => {}
+        }
+    }
+    // Frequencies alone annotate the default wordlist
+    if !lists.is_empty() && lists.iter().all(|&(op, _)| op == Op::Annotate) {
+        lists.insert(0, (Op::Union, "wordlists/enable1.txt"));
+    }
 
-        word.push(i);
-        for pos in board.any(i) {
-            path.push(pos);
-            descend(trie, board, &mut word, &mut path, &mut words);
-            path.pop();
+    //let dictionary = Dictionary::open("wordlists/yawl-0.3.2.03.list");
+    let opened = match lists.len() {
+        0 => Dictionary::open("wordlists/enable1.txt"),
+        1 if lists[0].0 == Op::Union => Dictionary::open(lists[0].1),
+        _ => lists.into_iter()
+            .map(|(op, path)| Dictionary::open(path).map(|d| (op, path.to_owned(), d)))
+            .collect::<std::io::Result<Vec<_>>>()
+            .and_then(|sources| Dictionary::compose(sources).map_err(std::io::Error::other))
+    };
+    let mut dictionary = match opened {
+        Ok(dictionary) => dictionary,
+        Err(e) => panic!("{:?}", e)
+    };
+
+    if let Some(path) = option(args, "--blocklist") {
+        match blocklist::Blocklist::open(path) {
+            Ok(blocklist) => {
+                let blocked = dictionary.block(&blocklist);
+                if log {
+                    let _ = writeln!(&mut std::io::stderr(), "Blocked Words: {}", blocked);
+                }
+            },
+            Err(e) => panic!("{:?}", e)
         }
-    });
+    }
+    if log {
+        let _ = writeln!(&mut std::io::stderr(), "Build Dictionary: {:?}", start.elapsed());
+    }
+    dictionary
 }
 
-/*
- * Keeps track of found words with a bitset keyed by word id.
- * Removing the word fom the trie would probably reduce the searching,
- * I'll look into it.
- */
+fn read_board(path: &str) -> SimpleBoggleBoard {
+    use std::fs::File;
+    use std::io::Read;
+
+    match File::open(path) {
+        Ok(mut file) => {
+            let mut buf = String::new();
+            file.read_to_string(&mut buf).unwrap();
+            SimpleBoggleBoard::read(buf.lines()).unwrap()
+        },
+        Err(e) => panic!("{:?}", e)
+    }
+}
 
-#[inline]
-#[allow(dead_code)]
-fn descend(parent: &Trie, board: &SimpleBoggleBoard, mut word: &mut Vec<u8>, mut path: &mut Vec<usize>, mut words: &mut BitSet) {
-    for (trie, i) in parent.iter() {
-        word.push(i);
-
-        for pos in board.neighbors(*path.last().unwrap(), i) {
-
-            if !path.contains(&pos) {
-                path.push(pos);
-
-                match trie.node_type() {
-                    NodeType::Word(id) => {
-                        if !words.get(id) {
-                            words.add(id);
-                            let mut found: Vec<u8> = word.clone();
-                            for b in found.iter_mut() {
-                                *b += 'a' as u8;
-                            }
-
-                            let s = unsafe { std::str::from_utf8_unchecked(&found) }.replace("q", "qu");
-                            println!("{}", s);
-                        }
-                    },
-                    _ => ()
-                }
+/// A board given as a compact code, see boggle::code
+fn read_code(code: &str) -> SimpleBoggleBoard {
+    match code::read(code) {
+        Ok(board) => board,
+        Err(e) => panic!("{:?}", e)
+    }
+}
 
+fn run_play(args: &[String]) {
+    use std::io::IsTerminal;
+    use std::time::Duration;
 
-                descend(trie, board, &mut word, &mut path, &mut words);
-                path.pop();
-            }
+    let dictionary = load_dictionary(args);
+
+    let session = match (option(args, "--board"), option(args, "--code")) {
+        (Some(path), _) => game::Session::with_board(&dictionary, read_board(path), 1, game::SystemClock),
+        (None, Some(code)) => game::Session::with_board(&dictionary, read_code(code), 1, game::SystemClock),
+        (None, None) => {
+            let size: usize = option(args, "--size").map(|s| s.parse().expect("Invalid size")).unwrap_or(4);
+            game::Session::new(&dictionary, size, size, 1, game::SystemClock, &mut rand::thread_rng())
+                .expect("Invalid size, boards are at least 2x2 and at most 256x256")
         }
-        word.pop();
+    };
+
+    let duration = match option(args, "--time") {
+        Some(secs) => Duration::from_secs(secs.parse().expect("Invalid time")),
+        None => game::DEFAULT_DURATION
+    };
+
+    let options = display::Options {
+        coordinates: false,
+        color: std::io::stdout().is_terminal() && !args.iter().any(|arg| arg == "--no-color"),
+    };
+
+    if let Err(e) = play::play(session, duration, &options) {
+        panic!("{:?}", e)
     }
 }
 
+fn run_serve(args: &[String]) {
+    use std::net::TcpListener;
 
-//==============================================================================
+    let dictionary = load_dictionary(args);
+    let port = option(args, "--port").unwrap_or("8080");
+    let workers = option(args, "--workers").map(|s| s.parse().expect("Invalid workers")).unwrap_or(4);
 
+    let listener = match TcpListener::bind(("127.0.0.1", port.parse().expect("Invalid port"))) {
+        Ok(listener) => listener,
+        Err(e) => panic!("{:?}", e)
+    };
+    eprintln!("Listening on http://{}", listener.local_addr().unwrap());
 
-/*
- * Here we have a the same funtion body, but with different signatures.
- * I'm not sure how to make the two board types impl the BoggleBoard trait without
- * boxing, which slows down the algo. (not even in nightly via impl trait)
- */
+    if let Err(e) = server::serve(&listener, &dictionary, workers) {
+        panic!("{:?}", e)
+    }
+}
 
-#[allow(dead_code)]
-fn solve_radix(root: &Trie, board: &RadixBoggleBoard) {
-    let mut word = Vec::with_capacity(64);
-    let mut path = Vec::with_capacity(64);
-    let mut words = BitSet::new();
-    for (trie, i) in root.iter() {
-        word.push(i);
-        for pos in board.any(i) {
-            path.push(pos);
-            descend_radix(trie, board, &mut word, &mut path, &mut words);
-            path.pop();
-        }
-        word.pop();
+fn run_engine(args: &[String]) {
+    // Only build a dictionary up front if asked, a front end can load its own
+    let dictionary = option(args, "--dict").map(|_| load_dictionary(args));
+
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    if let Err(e) = engine::Engine::new(dictionary).run(stdin.lock(), stdout.lock()) {
+        panic!("{:?}", e)
     }
 }
 
-#[allow(dead_code)]
-fn par_solve_radix(root: &Trie, board: &RadixBoggleBoard) {
+fn run_batch(args: &[String]) {
+    use std::io::Write;
+    use std::time::Instant;
 
-    let forest: Vec<(&Trie, u8)> = root.iter().collect();
-    forest.par_iter().for_each(|&(ref trie, i)| {
-        let mut word = Vec::with_capacity(64);
-        let mut path = Vec::with_capacity(64);
-        let mut words = BitSet::new();
+    let dictionary = load_dictionary(args);
 
-        word.push(i);
-        for pos in board.any(i) {
-            path.push(pos);
-            descend_radix(trie, board, &mut word, &mut path, &mut words);
-            path.pop();
-        }
-    });
+    let start = Instant::now();
+    let path = option(args, "--boards").expect("Missing --boards FILE|DIR");
+    let boards = match batch::load(path) {
+        Ok(boards) => boards,
+        Err(e) => panic!("{:?}", e)
+    };
+    let _ = writeln!(&mut std::io::stderr(), "Read Boards: {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let results = batch::solve_all(&dictionary, boards);
+    let _ = writeln!(&mut std::io::stderr(), "Batch Solve: {:?}", start.elapsed());
+
+    for result in &results {
+        println!("{}\t{}\t{}\t{}", result.name, result.solution.len(), result.score, result.longest().unwrap_or_default());
+    }
+
+    let stats = batch::Stats::of(&results);
+    println!("boards: {}", stats.boards);
+    println!("words: {} (min {}, mean {:.2}, max {})", stats.words, stats.min_words, stats.mean_words(), stats.max_words);
+    println!("score: {} (mean {:.2}, max {})", stats.score, stats.mean_score(), stats.max_score);
+    println!("solve time: {:?}", stats.solve_time);
 }
 
-#[inline]
-#[allow(dead_code)]
-fn descend_radix(parent: &Trie, board: &RadixBoggleBoard, mut word: &mut Vec<u8>, mut path: &mut Vec<usize>, mut words: &mut BitSet) {
-    for (trie, i) in parent.iter() {
-        word.push(i);
-
-        for pos in board.neighbors(*path.last().unwrap(), i) {
-
-            if !path.contains(&pos) {
-                path.push(pos);
-
-                match trie.node_type() {
-                    NodeType::Word(id) => {
-                        if !words.get(id) {
-                            words.add(id);
-                            let mut found: Vec<u8> = word.clone();
-                            for b in found.iter_mut() {
-                                *b += 'a' as u8;
-                            }
-
-                            let s = unsafe { std::str::from_utf8_unchecked(&found) }.replace("q", "qu");
-                            println!("{}", s);
-                        }
-                    },
-                    _ => ()
-                }
+/// Prints a board in each of the compact notations
+fn run_code(args: &[String]) {
+    let board = match (option(args, "--board"), option(args, "--code")) {
+        (Some(path), _) => read_board(path),
+        (None, Some(code)) => read_code(code),
+        (None, None) => panic!("Missing --board FILE or --code CODE")
+    };
+    let set = option(args, "--dice").map(|name| dice::Set::from_name(name).expect("Unknown dice set")).unwrap_or(dice::Set::Custom);
+
+    if let Some(square) = code::write_square(&board) {
+        println!("{}", square);
+    }
+    println!("{}", code::write_rows(&board));
+    println!("{}", code::encode(&board, set));
+}
 
+/// Prints a board in the terminal, with --explain WORD showing the word's path
+fn run_show(args: &[String]) {
+    use std::io::IsTerminal;
+
+    let board = match (option(args, "--board"), option(args, "--code")) {
+        (Some(path), _) => read_board(path),
+        (None, Some(code)) => read_code(code),
+        (None, None) => panic!("Missing --board FILE or --code CODE")
+    };
+    let options = display::Options {
+        coordinates: args.iter().any(|arg| arg == "--coords"),
+        color: std::io::stdout().is_terminal() && !args.iter().any(|arg| arg == "--no-color"),
+    };
+
+    match option(args, "--explain") {
+        Some(word) => match display::explain(&board, word, &options) {
+            Some(grid) => print!("{}", grid),
+            None => {
+                print!("{}", display::grid(&board, &[], &options));
+                println!("{} is not on the board", word);
+            }
+        },
+        None => print!("{}", display::grid(&board, &[], &options))
+    }
+}
 
-                descend_radix(trie, board, &mut word, &mut path, &mut words);
-                path.pop();
+/// Prints how many words, and points, pass through and start on each cell
+fn run_heatmap(args: &[String]) {
+    let board = match (option(args, "--board"), option(args, "--code")) {
+        (Some(path), _) => read_board(path),
+        (None, Some(code)) => read_code(code),
+        (None, None) => panic!("Missing --board FILE or --code CODE")
+    };
+    let radix_board = RadixBoggleBoard::from(&board);
+    let dictionary = load_dictionary(args);
+    let heatmap = heatmap::Heatmap::of(&radix_board, &solve_radix(dictionary.trie(), &radix_board));
+
+    match option(args, "--format") {
+        Some("json") => println!("{}", heatmap.to_json()),
+        Some("text") | None => {
+            println!("Words through each cell:\n{}", heatmap.grid(&heatmap.words));
+            println!("Points through each cell:\n{}", heatmap.grid(&heatmap.points));
+            println!("Words starting on each cell:\n{}", heatmap.grid(&heatmap.starts));
+            println!("Top starting cells:");
+            for (i, n) in heatmap.top_starts(5) {
+                println!("  [{}, {}] {} {}", i / board.width(), i % board.width(), display::face(board.get(i)), n);
             }
+            let dead: Vec<String> = heatmap.dead.iter().map(|&i| format!("[{}, {}]", i / board.width(), i % board.width())).collect();
+            println!("Dead cells: {}", if dead.is_empty() { "none".to_owned() } else { dead.join(" ") });
+        },
+        Some(format) => panic!("Invalid format {:?}, expected text or json", format)
+    }
+}
+
+/// Reports what loading a wordlist keeps and drops
+fn run_dict_check(args: &[String]) {
+    use std::fs::File;
+    use std::io::BufRead;
+    use std::io::BufReader;
+
+    let path = option(args, "--dict").unwrap_or("wordlists/enable1.txt");
+    let report = match File::open(path) {
+        Ok(file) => lint::check(BufReader::new(file).lines().map_while(Result::ok)),
+        Err(e) => panic!("{:?}", e)
+    };
+
+    match option(args, "--format") {
+        Some("json") => println!("{}", report.to_json()),
+        Some("text") | None => print!("{}", report),
+        Some(format) => panic!("Invalid format {:?}, expected text or json", format)
+    }
+}
+
+/// Lists dictionary words by prefix or pattern
+fn run_words(args: &[String]) {
+    let dictionary = load_dictionary(args);
+    let limit = option(args, "--limit").map(|s| s.parse().expect("Invalid limit")).unwrap_or(usize::MAX);
+    let count = args.iter().any(|arg| arg == "--count");
+
+    let words: Box<dyn Iterator<Item=(String, usize)>> = match (option(args, "--prefix"), option(args, "--pattern")) {
+        (Some(prefix), None) if count => return println!("{}", dictionary.count_prefixed(prefix)),
+        (Some(prefix), None) => Box::new(dictionary.prefixed(prefix)),
+        (None, Some(pattern)) => Box::new(dictionary.matching(pattern).unwrap_or_else(|e| panic!("{}", e))),
+        _ => panic!("Expected one of --prefix or --pattern")
+    };
+
+    if count {
+        println!("{}", words.count());
+    } else {
+        for (word, _) in words.take(limit) {
+            println!("{}", word);
         }
-        word.pop();
     }
 }
 
+/// Lists the words a rack of letters, or the dice of a board, can spell
+fn run_anagram(args: &[String]) {
+    let rack = match (option(args, "--letters"), option(args, "--board"), option(args, "--code")) {
+        (Some(letters), _, _) => anagram::Rack::parse(letters).unwrap_or_else(|e| panic!("{}", e)),
+        (None, Some(path), _) => anagram::Rack::of(&RadixBoggleBoard::from(&read_board(path))),
+        (None, None, Some(code)) => anagram::Rack::of(&RadixBoggleBoard::from(&read_code(code))),
+        (None, None, None) => panic!("Missing --letters RACK, --board FILE or --code CODE")
+    };
+    let dictionary = load_dictionary(args);
+    let limit = option(args, "--limit").map(|s| s.parse().expect("Invalid limit")).unwrap_or(usize::MAX);
+
+    let mut anagrams = if args.iter().any(|arg| arg == "--exact") {
+        anagram::exact(dictionary.trie(), &rack)
+    } else {
+        anagram::solve(dictionary.trie(), &rack)
+    };
+    // Longest first, as those are the ones worth playing
+    anagrams.sort_by_key(|a| std::cmp::Reverse(a.len()));
+
+    for anagram in anagrams.iter().take(limit) {
+        println!("{}", anagram.render());
+    }
+}
 
-//==============================================================================
+/// Solves a spelling bee puzzle, or generates one with --generate
+fn run_bee(args: &[String]) {
+    let dictionary = load_dictionary(args);
+
+    let (puzzle, answers) = if args.iter().any(|arg| arg == "--generate") {
+        let min = option(args, "--min").map(|s| s.parse().expect("Invalid min")).unwrap_or(20);
+        let max = option(args, "--max").map(|s| s.parse().expect("Invalid max")).unwrap_or(60);
+        let attempts = option(args, "--attempts").map(|s| s.parse().expect("Invalid attempts")).unwrap_or(1000);
+        match bee::generate(&dictionary, min, max, attempts, &mut rand::thread_rng()) {
+            Some(generated) => generated,
+            None => panic!("No puzzle with {} to {} words found in {} attempts", min, max, attempts)
+        }
+    } else {
+        let letters = option(args, "--letters").expect("Missing --letters LETTERS or --generate");
+        let center = option(args, "--center").and_then(|s| s.chars().next()).expect("Missing --center LETTER");
+        let puzzle = bee::Puzzle::parse(letters, center).unwrap_or_else(|e| panic!("{}", e));
+        (puzzle, bee::solve(dictionary.trie(), &puzzle))
+    };
+
+    println!("Puzzle: {}", puzzle);
+    for answer in answers.iter() {
+        println!("{:>3} {}{}", answer.points, answer.word, if answer.pangram { " (pangram)" } else { "" });
+    }
+    println!("{} words, {} pangrams, {} points", answers.len(),
+             answers.iter().filter(|a| a.pangram).count(), answers.iter().map(|a| a.points).sum::<u32>());
+}
 
+/// Rates a board easy, medium or hard from how common its words are
+fn run_difficulty(args: &[String]) {
+    let board = match (option(args, "--board"), option(args, "--code")) {
+        (Some(path), _) => read_board(path),
+        (None, Some(code)) => read_code(code),
+        (None, None) => panic!("Missing --board FILE or --code CODE")
+    };
+    let radix_board = RadixBoggleBoard::from(&board);
+
+    // Words take their frequencies from --freq FILE, a corpus by default
+    let mut annotated = args.to_vec();
+    if option(args, "--freq").is_none() {
+        annotated.extend(vec!["--freq".to_owned(), "wordlists/count_1w100k.txt".to_owned()]);
+    }
+    let dictionary = load_dictionary(&annotated);
+    let rating = difficulty::rate(&dictionary, &radix_board, &solve_radix(dictionary.trie(), &radix_board));
+
+    match option(args, "--format") {
+        Some("json") => {
+            let words: Vec<json::Json> = rating.words.iter().map(|w| json::object(vec![
+                ("word", w.word.as_str().into()),
+                ("count", (w.count as f64).into()),
+                ("chance", w.chance.into()),
+                ("points", w.points.into()),
+            ])).collect();
+            println!("{}", json::object(vec![
+                ("label", rating.label.name().into()),
+                ("expected", rating.expected.into()),
+                ("possible", rating.possible.into()),
+                ("words", json::Json::Array(words)),
+            ]));
+        },
+        Some("text") | None => {
+            for w in rating.words.iter() {
+                println!("{:5.1}% {}", w.chance * 100.0, w.word);
+            }
+            println!("{}: expect {:.1} of {} points", rating.label.name(), rating.expected, rating.possible);
+        },
+        Some(format) => panic!("Invalid format {:?}, expected text or json", format)
+    }
+}
 
-fn main() {
-    use std::time::Instant;
+/*
+ * Draws a board as SVG, with the paths of --words a,b,c as arrows. With
+ * --heatmap the board is solved and tiles shaded by how many words use them.
+ */
 
-    use std::io::BufReader;
-    use std::io::BufRead;
-    use std::io::Write;
+fn run_svg(args: &[String]) {
     use std::fs::File;
+    use std::io::Write;
 
-    let start = Instant::now();
+    let board = match (option(args, "--board"), option(args, "--code")) {
+        (Some(path), _) => read_board(path),
+        (None, Some(code)) => read_code(code),
+        (None, None) => panic!("Missing --board FILE or --code CODE")
+    };
+    let radix_board = RadixBoggleBoard::from(&board);
 
-    let mut trie = Trie::new();
-    
-    {
-        //let f = File::open("wordlists/yawl-0.3.2.03.list");
-        let f = File::open("wordlists/enable1.txt");
-
-        /*
-         * Boggle rules state words must be at least three characters, also
-         * there is no Q face on any die, it's replaced with a Qu. As any
-         * word containing Q not followed by u is illegal, we filter them out
-         * an replace 'qu' with 'q' here, and repace 'q' with 'qu' when solving.
-         * (This seperates the game rules from trie and board represention)
-         */
-        match f {
-            Ok(file) => {
-
-                /* 
-                 * This is ugly, it would be better to have another struct to
-                 * represent a dictionary and hold the root trie, the id counter,
-                 * and any other interesting statistics.
-                 */
-                let mut id = 0;
-
-                let reader = BufReader::new(&file);
-                for word in reader.lines()
-                                  .map(|result| result.unwrap())
-                                  .filter(|ref line| line.len() >= 3)
-                                  .filter(|ref line| {
-                                      let mut iter = line.chars();
-                                      while let Some(c) = iter.next() {
-                                          if c == 'q' || c == 'q' {
-                                            if let Some(n) = iter.next() {
-                                                if !(n == 'u' || n == 'U') {
-                                                    // q not followed by u
-                                                    return false;
-                                                }
-                                            } else {
-                                                // line ends in q
-                                                return false;
-                                            }
-                                          }
-                                      }
-                                      true
-                                  })
-                                  .map(|ref line| line.replace("qu", "q"))
-                {
-                    if trie.insert(&word, id) {
-                        id += 1;
-                    }
-                }
-            },
-            Err(e) => panic!("{:?}", e)
+    let mut style = svg::Style::default();
+    if let Some(color) = option(args, "--tile-color") {
+        style.tile = color.to_owned();
+    }
+    if let Some(color) = option(args, "--text-color") {
+        style.text = color.to_owned();
+    }
+    if let Some(color) = option(args, "--heat-color") {
+        style.heat = color.to_owned();
+    }
+    if let Some(colors) = option(args, "--path-colors") {
+        style.paths = colors.split(',').map(|c| c.to_owned()).collect();
+    }
+
+    let mut paths = Vec::new();
+    for word in option(args, "--words").into_iter().flat_map(|words| words.split(',')) {
+        match find_word_radix(&radix_board, word) {
+            Some(path) => paths.push(path),
+            None => panic!("{:?} is not on the board", word)
         }
-    }   
-    let _ = writeln!(&mut std::io::stderr(), "Build Dictionary: {:?}", start.elapsed());
+    }
+
+    let heat = if args.iter().any(|arg| arg == "--heatmap") {
+        let dictionary = load_dictionary(args);
+        let solution = solve_radix(dictionary.trie(), &radix_board);
+        Some(svg::word_heat(&radix_board, &solution))
+    } else {
+        None
+    };
+
+    let picture = svg::render(&board, &paths, heat.as_deref(), &style);
+    let written = match option(args, "--out") {
+        Some(path) => File::create(path).and_then(|mut file| file.write_all(picture.as_bytes())),
+        None => std::io::stdout().write_all(picture.as_bytes())
+    };
+    if let Err(e) = written {
+        panic!("{:?}", e)
+    }
+}
+
+fn run_solve(args: &[String]) {
+    use std::io::Write;
+    use std::time::Instant;
+
+    let format = match option(args, "--format") {
+        Some(name) => report::Format::parse(name).expect("Invalid format, expected text, json, ndjson or csv"),
+        None => report::Format::Text
+    };
+    // Machine readable formats keep stderr for their summary alone
+    let log = format == report::Format::Text;
 
     let start = Instant::now();
+    let dictionary = open_dictionary(args, log);
+    let trie = dictionary.trie();
+    let dictionary_time = start.elapsed();
 
-    let simple_board: SimpleBoggleBoard;
-    let radix_board: RadixBoggleBoard;
-    {
-        let f = File::open("boards/256x256.txt");
-        //let f = File::open("boards/1024x1024.txt");
-
-        match f {
-            Ok(mut file) => {
-                use std::io::Read;
-                let mut buf = String::new();
-                file.read_to_string(&mut buf).unwrap();
-                let s: &str = &buf;
-                simple_board = SimpleBoggleBoard::read(s.lines()).unwrap();
-
-                radix_board = RadixBoggleBoard::from(&simple_board);
-            },
-            Err(e) => panic!("{:?}", e)
-        }
+    let start = Instant::now();
+
+    //let path = "boards/1024x1024.txt";
+    let simple_board = match option(args, "--code") {
+        Some(code) => read_code(code),
+        None => read_board(option(args, "--board").unwrap_or("boards/256x256.txt"))
+    };
+    let radix_board = RadixBoggleBoard::from(&simple_board);
+
+    let board_time = start.elapsed();
+    if log {
+        let _ = writeln!(&mut std::io::stderr(), "Build Board: {:?}", board_time);
     }
-    let _ = writeln!(&mut std::io::stderr(), "Build Board: {:?}", start.elapsed());
 
     // Leaving this here as poor man's perf tests
 
@@ -304,10 +561,25 @@ fn main() {
     let _ = writeln!(&mut std::io::stderr(), "Paralell Solve (Simple): {:?}", start.elapsed());*/
 
     let start = Instant::now();
-    solve_radix(&trie, &radix_board);
-    let _ = writeln!(&mut std::io::stderr(), "Sequential Solve (Radix): {:?}", start.elapsed());
+    let mut solution = solve_radix(trie, &radix_board);
+    let solve_time = start.elapsed();
+
+    // Only words from one of the composed wordlists
+    if let Some(name) = option(args, "--source") {
+        let k = dictionary.source(name).expect("Unknown --source, expected a composed wordlist") as u32;
+        solution.retain(|found| dictionary.meta(found.id).sources.get(k));
+    }
+    if log {
+        let _ = writeln!(&mut std::io::stderr(), "Sequential Solve (Radix): {:?}", solve_time);
+    }
+
+    let timings = report::Timings { dictionary: dictionary_time, board: board_time, solve: solve_time };
+    let stdout = std::io::stdout();
+    if let Err(e) = report::write(&mut stdout.lock(), &mut std::io::stderr(), format, &dictionary, &radix_board, &solution, &timings) {
+        panic!("{:?}", e)
+    }
 
     /*let start = Instant::now();
-    par_solve_radix(&trie, &radix_board);
+    par_solve_radix(trie, &radix_board);
     let _ = writeln!(&mut std::io::stderr(), "Paralell Solve (Radix): {:?}", start.elapsed());*/
-}
\ No newline at end of file
+}
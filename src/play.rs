@@ -0,0 +1,120 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Interactive single player game in the terminal
+ *
+ * Words are read from stdin on their own thread, so the clock can end the
+ * round while the player is still typing.
+ */
+
+use std::io;
+use std::io::BufRead;
+use std::io::Write;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use dictionary::MIN_LENGTH;
+use display;
+use game::*;
+
+pub fn play(mut session: Session<SystemClock>, duration: Duration, options: &display::Options) -> io::Result<()> {
+    session.set_duration(duration).unwrap();
+
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let stdin = io::stdin();
+        for line in stdin.lock().lines() {
+            match line {
+                Ok(line) => if tx.send(line).is_err() { break },
+                Err(_) => break
+            }
+        }
+    });
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    writeln!(out, "Find as many words as you can, an empty line redraws the board.")?;
+    session.start().unwrap();
+
+    let mut path: Vec<usize> = Vec::new();
+    write!(out, "\n{}", display::grid(session.board(), &path, options))?;
+
+    while session.state() == State::Running {
+        let remaining = session.remaining();
+        write!(out, "[{}:{:02}] > ", remaining.as_secs() / 60, remaining.as_secs() % 60)?;
+        out.flush()?;
+
+        let word = match rx.recv_timeout(remaining) {
+            Ok(word) => word,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                writeln!(out, "\nTime's up!")?;
+                break;
+            },
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                session.finish().unwrap();
+                writeln!(out)?;
+                break;
+            }
+        };
+
+        let word = word.trim();
+        if word.is_empty() {
+            write!(out, "\n{}", display::grid(session.board(), &path, options))?;
+            continue;
+        }
+
+        match session.submit(0, word) {
+            Ok(Verdict::Accepted(p, points)) => {
+                path = p;
+                write!(out, "\n{}", display::grid(session.board(), &path, options))?;
+                writeln!(out, "{} +{}", word.to_lowercase(), points)?;
+            },
+            Ok(Verdict::TooShort) => writeln!(out, "Too short, words need at least {} letters", MIN_LENGTH)?,
+            Ok(Verdict::NotInDictionary) => writeln!(out, "Not in the dictionary")?,
+            Ok(Verdict::NotOnBoard) => writeln!(out, "Not on the board")?,
+            Ok(Verdict::AlreadyFound) => writeln!(out, "Already found")?,
+            Ok(Verdict::InvalidCharacters) => writeln!(out, "Letters only please")?,
+            Err(_) => break
+        }
+    }
+
+    let results = session.results().unwrap();
+    let player = &results.players[0];
+    let found = player.words.iter().filter(|w| matches!(w.verdict, Verdict::Accepted(..))).count();
+    writeln!(out, "\nYou scored {} of {} points, finding {} of {} words.",
+             player.score, results.possible, found, found + results.missed.len())?;
+
+    if !results.missed.is_empty() {
+        writeln!(out, "\nYou missed:")?;
+        for word in results.missed.iter() {
+            writeln!(out, "  {}", word)?;
+        }
+    }
+
+    Ok(())
+}
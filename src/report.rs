@@ -0,0 +1,210 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Writes a solution in a format other tools can read
+ *
+ *   text    the words, one per line
+ *   json    one object holding every word and the summary
+ *   ndjson  one object per line, each word then the summary, told apart by "type"
+ *   csv     a header and one row per word, the summary going to stderr
+ *
+ * Each word carries its length as spelled, its points, the first path
+ * found as [row, column] pairs, how many paths spell it, and its frequency
+ * if the wordlist had one.
+ */
+
+use std::io;
+use std::io::Write;
+use std::time::Duration;
+
+use api::path_to_json;
+use boggle::RadixBoggleBoard;
+use dictionary::Dictionary;
+use game::points;
+use json;
+use json::Json;
+use solver::Found;
+use solver::Solution;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Ndjson,
+    Csv,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "ndjson" => Some(Format::Ndjson),
+            "csv" => Some(Format::Csv),
+            _ => None
+        }
+    }
+}
+
+/// Where the time went
+pub struct Timings {
+    pub dictionary: Duration,
+    pub board: Duration,
+    pub solve: Duration,
+}
+
+pub fn write<W: Write, E: Write>(out: &mut W, err: &mut E, format: Format, dictionary: &Dictionary,
+                                 board: &RadixBoggleBoard, solution: &Solution, timings: &Timings) -> io::Result<()> {
+    let render = |found: &Found| found.render(|pos| !board.get(pos).is_letter());
+
+    match format {
+        Format::Text => {
+            for found in solution.iter() {
+                writeln!(out, "{}", render(found))?;
+            }
+        },
+        Format::Json => {
+            let words: Vec<Json> = solution.iter().map(|found| word(dictionary, board, found, render(found))).collect();
+            writeln!(out, "{}", json::object(vec![
+                ("words", Json::Array(words)),
+                ("summary", summary(board, solution, timings)),
+            ]))?;
+        },
+        Format::Ndjson => {
+            for found in solution.iter() {
+                writeln!(out, "{}", tagged("word", word(dictionary, board, found, render(found))))?;
+            }
+            writeln!(out, "{}", tagged("summary", summary(board, solution, timings)))?;
+        },
+        Format::Csv => {
+            writeln!(out, "word,length,score,path,paths,frequency")?;
+            for found in solution.iter() {
+                let frequency = dictionary.meta(found.id).frequency.map(|f| f.to_string()).unwrap_or_default();
+                writeln!(out, "{},{},{},\"{}\",{},{}", render(found), found.len(), points(found.len()),
+                         path_to_json(&found.path, board.width()), found.paths, frequency)?;
+            }
+            writeln!(err, "{}", summary(board, solution, timings))?;
+        },
+    }
+    Ok(())
+}
+
+fn word(dictionary: &Dictionary, board: &RadixBoggleBoard, found: &Found, rendered: String) -> Json {
+    json::object(vec![
+        ("word", rendered.into()),
+        ("length", found.len().into()),
+        ("score", points(found.len()).into()),
+        ("path", path_to_json(&found.path, board.width())),
+        ("paths", found.paths.into()),
+        ("frequency", dictionary.meta(found.id).frequency.map_or(Json::Null, |f| (f as f64).into())),
+    ])
+}
+
+pub fn summary(board: &RadixBoggleBoard, solution: &Solution, timings: &Timings) -> Json {
+    let score: u32 = solution.iter().map(|found| points(found.len())).sum();
+    let paths: usize = solution.iter().map(|found| found.paths).sum();
+
+    json::object(vec![
+        ("width", board.width().into()),
+        ("height", board.height().into()),
+        ("words", solution.len().into()),
+        ("score", score.into()),
+        ("paths", paths.into()),
+        ("timings_ms", json::object(vec![
+            ("dictionary", millis(timings.dictionary)),
+            ("board", millis(timings.board)),
+            ("solve", millis(timings.solve)),
+        ])),
+    ])
+}
+
+/// Puts a "type" member first, so NDJSON readers can tell lines apart
+fn tagged(kind: &str, value: Json) -> Json {
+    match value {
+        Json::Object(mut members) => {
+            members.insert(0, ("type".to_owned(), kind.into()));
+            Json::Object(members)
+        },
+        value => value
+    }
+}
+
+fn millis(duration: Duration) -> Json {
+    (duration.as_secs_f64() * 1000.0).into()
+}
+
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use dictionary::Dictionary;
+    use fixture;
+    use json::Json;
+    use solver;
+    use super::*;
+
+    fn run(format: Format) -> (String, String) {
+        let words = fixture::WORDS.iter().map(|&w| if w == "queen" { "queen\t1200".to_owned() } else { w.to_owned() });
+        let dictionary = Dictionary::read(words);
+        let board = fixture::radix("qet\nena\n?.s");
+        let solution = solver::solve_radix(dictionary.trie(), &board);
+        let timings = Timings { dictionary: Duration::from_millis(2), board: Duration::from_micros(1500), solve: Duration::from_millis(1) };
+
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        write(&mut out, &mut err, format, &dictionary, &board, &solution, &timings).unwrap();
+        (String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn writes_csv_rows() {
+        let (out, err) = run(Format::Csv);
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines[0], "word,length,score,path,paths,frequency");
+        assert!(lines.contains(&"queen,5,2,\"[[0,0],[0,1],[1,0],[1,1]]\",5,1200"));
+        assert!(lines.contains(&"tan,3,1,\"[[0,2],[1,2],[1,1]]\",1,"));
+        let summary = Json::parse(err.trim()).unwrap();
+        assert_eq!(summary.get("words").unwrap().to_string(), (lines.len() - 1).to_string());
+        assert_eq!(summary.get("timings_ms").unwrap().to_string(), r#"{"dictionary":2,"board":1.5,"solve":1}"#);
+    }
+
+    #[test]
+    fn json_and_ndjson_agree() {
+        let (out, err) = run(Format::Json);
+        let all = Json::parse(&out).unwrap();
+        assert!(err.is_empty());
+
+        let (out, _) = run(Format::Ndjson);
+        let lines: Vec<Json> = out.lines().map(|line| Json::parse(line).unwrap()).collect();
+        let (summary, words) = lines.split_last().unwrap();
+
+        assert_eq!(summary.get("type").and_then(|t| t.as_str()), Some("summary"));
+        assert_eq!(summary.get("score").unwrap().to_string(), all.get("summary").unwrap().get("score").unwrap().to_string());
+        assert_eq!(words.len(), all.get("words").and_then(|w| w.as_array()).unwrap().len());
+        assert!(words.iter().all(|w| w.get("type").and_then(|t| t.as_str()) == Some("word")));
+    }
+}
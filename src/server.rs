@@ -0,0 +1,359 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * A small HTTP server exposing the solver as a JSON API on localhost
+ *
+ *   POST /solve     {"board": ...}                     -> the words on the board
+ *   POST /validate  {"board": ..., "words": [...]}     -> a verdict for each word
+ *   GET  /generate  ?size=N or ?width=W&height=H       -> a freshly rolled board
+ *
+ * Boards, words and verdicts take the JSON forms of the api module.
+ * The dictionary is loaded once and shared by every request. Requests are
+ * handled on a rayon thread pool, one connection at a time per worker.
+ */
+
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::panic;
+use std::time::Duration;
+use std::time::Instant;
+
+use rand;
+use rayon::prelude::*;
+use rayon::Configuration;
+use rayon::ThreadPool;
+
+use api::*;
+use boggle::*;
+use dictionary::Dictionary;
+use json;
+use json::Json;
+
+/// Requests larger than this (headers and body) are refused
+pub const MAX_REQUEST: usize = 64 * 1024;
+
+/// Time a client gets to send its whole request, and to take the response
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    body: String,
+}
+
+struct Response {
+    status: u16,
+    body: Json,
+}
+
+impl Response {
+    fn ok(body: Json) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Response { status, body: json::object(vec![("error", message.into())]) }
+    }
+}
+
+/// Serves requests on the listener forever, with the given number of workers
+pub fn serve(listener: &TcpListener, dictionary: &Dictionary, workers: usize) -> io::Result<()> {
+    let pool = ThreadPool::new(Configuration::new().set_num_threads(workers))
+        .map_err(|e| io::Error::other(e.to_string()))?;
+
+    // Every worker accepts connections off the same listener
+    pool.install(|| (0..workers).into_par_iter().for_each(|_| {
+        for stream in listener.incoming().flatten() {
+            handle(stream, dictionary);
+        }
+    }));
+
+    Ok(())
+}
+
+fn handle(mut stream: TcpStream, dictionary: &Dictionary) {
+    let _ = stream.set_write_timeout(Some(TIMEOUT));
+
+    // A bug in a handler costs its request, not the worker
+    let response = match read_request(&mut stream) {
+        Ok(request) => panic::catch_unwind(|| route(&request, dictionary))
+            .unwrap_or_else(|_| Response::error(500, "Internal error")),
+        Err(response) => response
+    };
+
+    let body = response.body.to_string();
+    let _ = write!(stream, "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                   response.status, reason(response.status), body.len(), body);
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+fn read_request(stream: &mut TcpStream) -> Result<Request, Response> {
+    let deadline = Instant::now() + TIMEOUT;
+    let mut buf = Vec::with_capacity(1024);
+
+    // Read until the end of the headers
+    let header_len = loop {
+        if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i + 4;
+        }
+        if buf.len() > MAX_REQUEST {
+            return Err(Response::error(413, "Request too large"));
+        }
+        read_before(stream, &mut buf, deadline)?;
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_len]).into_owned();
+    let mut lines = head.split("\r\n");
+
+    let mut request_line = lines.next().unwrap_or("").split(' ');
+    let method = request_line.next().unwrap_or("").to_owned();
+    let target = request_line.next().unwrap_or("");
+    let (path, query) = match target.find('?') {
+        Some(i) => (target[..i].to_owned(), target[i + 1..].to_owned()),
+        None => (target.to_owned(), String::new())
+    };
+
+    let mut content_length = 0;
+    for line in lines {
+        if let Some(i) = line.find(':') {
+            if line[..i].eq_ignore_ascii_case("content-length") {
+                content_length = match line[i + 1..].trim().parse() {
+                    Ok(n) => n,
+                    Err(_) => return Err(Response::error(400, "Invalid Content-Length"))
+                };
+            }
+        }
+    }
+
+    // A huge Content-Length must not wrap around past the limit
+    let len = match header_len.checked_add(content_length) {
+        Some(len) if len <= MAX_REQUEST => len,
+        _ => return Err(Response::error(413, "Request too large"))
+    };
+
+    while buf.len() < len {
+        read_before(stream, &mut buf, deadline)?;
+    }
+
+    match String::from_utf8(buf[header_len..len].to_vec()) {
+        Ok(body) => Ok(Request { method, path, query, body }),
+        Err(_) => Err(Response::error(400, "Body is not UTF-8"))
+    }
+}
+
+/// Reads more of the request, as long as the deadline hasn't passed
+fn read_before(stream: &mut TcpStream, buf: &mut Vec<u8>, deadline: Instant) -> Result<(), Response> {
+    let left = deadline.saturating_duration_since(Instant::now());
+    if left == Duration::from_secs(0) {
+        return Err(Response::error(408, "Request timeout"));
+    }
+    let _ = stream.set_read_timeout(Some(left));
+
+    let mut chunk = [0u8; 1024];
+    match stream.read(&mut chunk) {
+        Ok(0) => Err(Response::error(400, "Incomplete request")),
+        Ok(n) => {
+            buf.extend_from_slice(&chunk[..n]);
+            Ok(())
+        },
+        Err(ref e) if e.kind() == io::ErrorKind::WouldBlock || e.kind() == io::ErrorKind::TimedOut => {
+            Err(Response::error(408, "Request timeout"))
+        },
+        Err(_) => Err(Response::error(400, "Incomplete request"))
+    }
+}
+
+fn route(request: &Request, dictionary: &Dictionary) -> Response {
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/solve") => parse_body(request).and_then(|body| {
+            let board = board_from_json(body.get("board"))?;
+            Ok(solve(dictionary, &RadixBoggleBoard::from(&board)))
+        }),
+        ("POST", "/validate") => parse_body(request).and_then(|body| {
+            let board = board_from_json(body.get("board"))?;
+            validate(dictionary, &RadixBoggleBoard::from(&board), body.get("words"))
+        }),
+        ("GET", "/generate") => generate(&request.query),
+        (_, "/solve") | (_, "/validate") | (_, "/generate") => return Response::error(405, "Method not allowed"),
+        _ => return Response::error(404, "Not found")
+    };
+
+    match result {
+        Ok(body) => Response::ok(body),
+        Err(message) => Response::error(400, message)
+    }
+}
+
+fn parse_body(request: &Request) -> Result<Json, &'static str> {
+    Json::parse(&request.body)
+}
+
+fn generate(query: &str) -> Result<Json, &'static str> {
+    let param = |name: &str| -> Result<Option<usize>, &'static str> {
+        match query.split('&').find(|kv| kv.split('=').next() == Some(name)) {
+            Some(kv) => kv.split('=').nth(1).and_then(|v| v.parse().ok()).map(Some).ok_or("Invalid size"),
+            None => Ok(None)
+        }
+    };
+
+    let size = param("size")?.unwrap_or(4);
+    let width = param("width")?.unwrap_or(size);
+    let height = param("height")?.unwrap_or(size);
+    match width.checked_mul(height) {
+        Some(cells) if width >= 2 && height >= 2 && cells <= MAX_CELLS => (),
+        _ => return Err("Invalid size")
+    }
+
+    let board = dice::generate(width, height, &mut rand::thread_rng());
+    Ok(json::object(vec![
+        ("width", width.into()),
+        ("height", height.into()),
+        ("board", board_to_json(&board)),
+        ("code", code::encode(&board, dice::Set::for_size(width, height)).into()),
+    ]))
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+    use std::io::Write;
+    use std::net::TcpListener;
+    use std::net::TcpStream;
+    use std::thread;
+
+    use dictionary::Dictionary;
+    use fixture;
+    use json::Json;
+    use super::serve;
+
+    /// Starts a server on an ephemeral port, returning its address
+    fn start() -> String {
+        let dictionary: &'static Dictionary = Box::leak(Box::new(fixture::dictionary()));
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || serve(&listener, dictionary, 2));
+        addr
+    }
+
+    fn request(addr: &str, raw: &str) -> (u16, Json) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(raw.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let status = response[9..12].parse().unwrap();
+        let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+        (status, Json::parse(body).unwrap())
+    }
+
+    fn post(addr: &str, path: &str, body: &str) -> (u16, Json) {
+        request(addr, &format!("POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}", path, body.len(), body))
+    }
+
+    #[test]
+    fn solves_boards() {
+        let addr = start();
+        let (status, body) = post(&addr, "/solve", r#"{"board": ["qet", "ena", "..s"]}"#);
+
+        assert_eq!(status, 200);
+        assert_eq!(body.get("count").and_then(|c| c.as_u64()), Some(4));
+        assert_eq!(body.get("score").and_then(|c| c.as_u64()), Some(5));
+        let words = body.get("words").and_then(|w| w.as_array()).unwrap();
+        let queen = words.iter().find(|w| w.get("word").and_then(|w| w.as_str()) == Some("queen")).unwrap();
+        assert_eq!(queen.get("path").unwrap().to_string(), "[[0,0],[0,1],[1,0],[1,1]]");
+        assert_eq!(queen.get("points").and_then(|p| p.as_u64()), Some(2));
+    }
+
+    #[test]
+    fn validates_words() {
+        let addr = start();
+        let (status, body) = post(&addr, "/validate", r#"{"board": "qet/ena/..s", "words": ["tan", "tan", "seat", "at"]}"#);
+
+        assert_eq!(status, 200);
+        assert_eq!(body.get("score").and_then(|c| c.as_u64()), Some(1));
+        let verdicts: Vec<&str> = body.get("results").and_then(|r| r.as_array()).unwrap()
+            .iter().map(|r| r.get("verdict").and_then(|v| v.as_str()).unwrap()).collect();
+        assert_eq!(verdicts, vec!["accepted", "already_found", "not_on_board", "too_short"]);
+    }
+
+    #[test]
+    fn generates_boards() {
+        let addr = start();
+        let (status, body) = request(&addr, "GET /generate?width=5&height=3 HTTP/1.1\r\n\r\n");
+
+        assert_eq!(status, 200);
+        let rows = body.get("board").and_then(|b| b.as_array()).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert!(rows.iter().all(|row| row.as_str().unwrap().len() == 5));
+        assert!(body.get("code").and_then(|c| c.as_str()).unwrap().starts_with("5x3-classic-"));
+    }
+
+    #[test]
+    fn rejects_bad_requests() {
+        let addr = start();
+
+        assert_eq!(post(&addr, "/solve", "{").0, 400);
+        assert_eq!(post(&addr, "/solve", r#"{"board": 1}"#).0, 400);
+        assert_eq!(post(&addr, "/nope", "{}").0, 404);
+        assert_eq!(request(&addr, "GET /solve HTTP/1.1\r\n\r\n").0, 405);
+        assert_eq!(request(&addr, "GET /generate?size=x HTTP/1.1\r\n\r\n").0, 400);
+        assert_eq!(request(&addr, "GET /generate?width=4294967296&height=4294967296 HTTP/1.1\r\n\r\n").0, 400);
+
+        // Ragged rows are padded out to the widest, which would make this 301x300
+        let rows = vec!["ab".to_owned(); 299].into_iter().chain(Some("a".repeat(301))).collect::<Vec<String>>().join("/");
+        assert_eq!(post(&addr, "/solve", &format!(r#"{{"board": "{}"}}"#, rows)).0, 400);
+        assert_eq!(request(&addr, "POST /solve HTTP/1.1\r\nContent-Length: 1000000\r\n\r\n").0, 413);
+        assert_eq!(request(&addr, "POST /solve HTTP/1.1\r\nContent-Length: 18446744073709551615\r\n\r\n").0, 413);
+        assert_eq!(post(&addr, "/solve", r#"{"board": "qet/ena/..s"}"#).0, 200);
+    }
+
+    #[test]
+    fn keeps_serving_after_bad_boards() {
+        let addr = start();
+
+        // More bad boards than workers, each of which used to take one down
+        for _ in 0..3 {
+            assert_eq!(post(&addr, "/solve", r#"{"board": ["a", "b"]}"#).0, 400);
+            assert_eq!(post(&addr, "/solve", r#"{"board": "1x2-custom-ab"}"#).0, 400);
+        }
+        assert_eq!(post(&addr, "/solve", r#"{"board": "qet/ena/..s"}"#).0, 200);
+    }
+}
@@ -0,0 +1,652 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+use std::cmp::max;
+use std::collections::HashMap;
+use std::slice;
+
+use trie::NodeType;
+use trie::Trie;
+use boggle::*;
+use boggle_util;
+
+use rayon::prelude::*;
+
+/// A word found on the board, and the first path it was found along
+#[derive(Clone, Debug)]
+pub struct Found {
+    pub id: usize,
+    /// Letters of the word, 'q' standing for "qu"
+    pub word: Vec<u8>,
+    /// Cells of the path, in the order the word is spelled
+    pub path: Vec<usize>,
+    /// How many distinct paths spell the word
+    pub paths: usize,
+}
+
+impl Found {
+    /// Length of the word as a player spells it, Qu counting as two letters
+    pub fn len(&self) -> usize {
+        spelled_len(&self.word)
+    }
+
+    /*
+     * Spells out the word, 'q' is rendered as "qu". Letters taken by a blank
+     * are uppercased, so the player can tell which letter each blank stood for.
+     */
+    pub fn render<F>(&self, is_blank: F) -> String where F: Fn(usize) -> bool {
+        let mut s = String::with_capacity(self.word.len() + 1);
+        for (&v, &pos) in self.word.iter().zip(self.path.iter()) {
+            let face = boggle_util::idx_to_face(v);
+            if is_blank(pos) {
+                s.push_str(&face.to_uppercase());
+            } else {
+                s.push_str(face);
+            }
+        }
+        s
+    }
+}
+
+/// Length of a word held as the trie spells it, once written out with each 'q' as "qu"
+pub fn spelled_len(word: &[u8]) -> usize {
+    word.len() + word.iter().filter(|&&v| v == b'q' - b'a').count()
+}
+
+/// The words found on a board, in the order they were found
+#[derive(Default)]
+pub struct Solution {
+    words: Vec<Found>,
+    /// Position of each word in `words`, keyed by word id
+    /// (Sparse, as batches keep many small solutions around at once)
+    index: HashMap<usize, usize>,
+}
+
+impl Solution {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    #[allow(dead_code)]
+    pub fn contains(&self, id: usize) -> bool {
+        self.index.contains_key(&id)
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, id: usize) -> Option<&Found> {
+        self.index.get(&id).map(|&i| &self.words[i])
+    }
+
+    fn get_mut(&mut self, id: usize) -> Option<&mut Found> {
+        match self.index.get(&id) {
+            Some(&i) => Some(&mut self.words[i]),
+            None => None
+        }
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, Found> {
+        self.words.iter()
+    }
+
+    /// Counts another path for a word, keeping the first path found
+    fn record(&mut self, id: usize, word: &[u8], path: &[usize]) {
+        match self.get_mut(id) {
+            Some(found) => found.paths += 1,
+            None => self.insert(Found { id, word: word.to_vec(), path: path.to_vec(), paths: 1 })
+        }
+    }
+
+    /// Keeps only the words matching the predicate
+    pub fn retain<F>(&mut self, mut keep: F) where F: FnMut(&Found) -> bool {
+        self.words.retain(|found| keep(found));
+        self.index = self.words.iter().enumerate().map(|(i, found)| (found.id, i)).collect();
+    }
+
+    fn insert(&mut self, found: Found) {
+        self.index.insert(found.id, self.words.len());
+        self.words.push(found);
+    }
+
+    fn remove(&mut self, id: usize) -> Option<Found> {
+        let i = self.index.remove(&id)?;
+        let found = self.words.swap_remove(i);
+        if let Some(moved) = self.words.get(i) {
+            self.index.insert(moved.id, i);
+        }
+        Some(found)
+    }
+
+    /// Merges the words of a solution found on a disjoint part of the trie
+    fn append(&mut self, other: Solution) {
+        for found in other.words {
+            self.insert(found);
+        }
+    }
+}
+
+
+/*
+ * Recursively search for dictionary words on the boggle board
+ *
+ * By using a prefix trie we prune words that cant be found
+ * (because their prefixes aren't on the board)
+ */
+
+#[allow(dead_code)]
+pub fn solve(root: &Trie, board: &SimpleBoggleBoard) -> Solution {
+    let mut word = Vec::with_capacity(64);
+    let mut path = Vec::with_capacity(64);
+    let mut solution = Solution::new();
+    for (trie, i) in root.iter() {
+        word.push(i);
+        for pos in board.any(i) {
+            path.push(pos);
+            descend(trie, board, &mut word, &mut path, &mut solution);
+            path.pop();
+        }
+        word.pop();
+    }
+    solution
+}
+
+#[allow(dead_code)]
+pub fn par_solve(root: &Trie, board: &SimpleBoggleBoard) -> Solution {
+
+    let forest: Vec<(&Trie, u8)> = root.iter().collect();
+    let mut solutions = Vec::with_capacity(forest.len());
+    forest.par_iter().map(|&(trie, i)| {
+        let mut word = Vec::with_capacity(64);
+        let mut path = Vec::with_capacity(64);
+        let mut solution = Solution::new();
+
+        word.push(i);
+        for pos in board.any(i) {
+            path.push(pos);
+            descend(trie, board, &mut word, &mut path, &mut solution);
+            path.pop();
+        }
+        solution
+    }).collect_into(&mut solutions);
+
+    let mut solution = Solution::new();
+    for s in solutions {
+        solution.append(s);
+    }
+    solution
+}
+
+/*
+ * Keeps track of found words in a solution keyed by word id.
+ * Removing the word fom the trie would probably reduce the searching,
+ * I'll look into it.
+ */
+
+#[inline]
+#[allow(dead_code)]
+fn descend(parent: &Trie, board: &SimpleBoggleBoard, word: &mut Vec<u8>, path: &mut Vec<usize>, solution: &mut Solution) {
+    for (trie, i) in parent.iter() {
+        word.push(i);
+
+        for pos in board.neighbors(*path.last().unwrap(), i) {
+
+            if !path.contains(&pos) {
+                path.push(pos);
+
+                if let NodeType::Word(id) = trie.node_type() {
+                    solution.record(id, word, path);
+                }
+
+
+                descend(trie, board, word, path, solution);
+                path.pop();
+            }
+        }
+        word.pop();
+    }
+}
+
+
+//==============================================================================
+
+
+/*
+ * Here we have a the same funtion body, but with different signatures.
+ * I'm not sure how to make the two board types impl the BoggleBoard trait without
+ * boxing, which slows down the algo. (not even in nightly via impl trait)
+ */
+
+#[allow(dead_code)]
+pub fn solve_radix(root: &Trie, board: &RadixBoggleBoard) -> Solution {
+    let mut word = Vec::with_capacity(64);
+    let mut path = Vec::with_capacity(64);
+    let mut solution = Solution::new();
+    for (trie, i) in root.iter() {
+        word.push(i);
+        for pos in board.any(i) {
+            path.push(pos);
+            descend_radix(trie, board, &mut word, &mut path, &mut solution);
+            path.pop();
+        }
+        word.pop();
+    }
+    solution
+}
+
+#[allow(dead_code)]
+pub fn par_solve_radix(root: &Trie, board: &RadixBoggleBoard) -> Solution {
+
+    let forest: Vec<(&Trie, u8)> = root.iter().collect();
+    let mut solutions = Vec::with_capacity(forest.len());
+    forest.par_iter().map(|&(trie, i)| {
+        let mut word = Vec::with_capacity(64);
+        let mut path = Vec::with_capacity(64);
+        let mut solution = Solution::new();
+
+        word.push(i);
+        for pos in board.any(i) {
+            path.push(pos);
+            descend_radix(trie, board, &mut word, &mut path, &mut solution);
+            path.pop();
+        }
+        solution
+    }).collect_into(&mut solutions);
+
+    let mut solution = Solution::new();
+    for s in solutions {
+        solution.append(s);
+    }
+    solution
+}
+
+#[inline]
+#[allow(dead_code)]
+fn descend_radix(parent: &Trie, board: &RadixBoggleBoard, word: &mut Vec<u8>, path: &mut Vec<usize>, solution: &mut Solution) {
+    for (trie, i) in parent.iter() {
+        word.push(i);
+
+        for pos in board.neighbors(*path.last().unwrap(), i) {
+
+            if !path.contains(&pos) {
+                path.push(pos);
+
+                if let NodeType::Word(id) = trie.node_type() {
+                    solution.record(id, word, path);
+                }
+
+
+                descend_radix(trie, board, word, path, solution);
+                path.pop();
+            }
+        }
+        word.pop();
+    }
+}
+
+
+//==============================================================================
+
+
+/// The words gained and lost by editing one cell
+pub struct Delta {
+    pub added: Vec<Found>,
+    pub removed: Vec<Found>,
+}
+
+/*
+ * Sets cell i of the board and brings a previous solution of it up to date.
+ *
+ * Only words with a path through the edited cell can be gained or lost, so
+ * we search just those paths, before and after the edit. A word that loses
+ * its path through the cell survives if it has another path avoiding it,
+ * as those paths are the same on both boards. That also keeps path counts
+ * exact: the paths through the cell are swapped for those found after.
+ */
+
+#[allow(dead_code)]
+pub fn resolve_radix(root: &Trie, board: &mut RadixBoggleBoard, solution: &mut Solution, i: usize, cell: Cell) -> Delta {
+    let before = solve_through_radix(root, board, i);
+    board.set(i, cell);
+    let mut after = solve_through_radix(root, board, i);
+
+    let mut delta = Delta { added: Vec::new(), removed: Vec::new() };
+
+    for found in before.words {
+        let new = after.remove(found.id);
+        let old = match solution.get_mut(found.id) {
+            Some(old) => old,
+            None => continue
+        };
+        old.paths = old.paths + new.as_ref().map_or(0, |new| new.paths) - found.paths;
+        let through = old.path.contains(&i);
+
+        match new {
+            // Still found through the cell, the old path may not be valid anymore
+            Some(new) => if through {
+                old.path = new.path;
+            },
+            None if old.paths == 0 => {
+                delta.removed.push(solution.remove(found.id).unwrap());
+            },
+            None => if through {
+                let mut path = Vec::with_capacity(old.word.len());
+                find_path_radix(board, &old.word, &mut path, Some(i));
+                old.path = path;
+            }
+        }
+    }
+
+    // Whatever is left was not found through the cell before the edit
+    for found in after.words {
+        match solution.get_mut(found.id) {
+            Some(old) => old.paths += found.paths,
+            None => {
+                delta.added.push(found.clone());
+                solution.insert(found);
+            }
+        }
+    }
+
+    delta
+}
+
+/// Finds every word with a path through cell i
+fn solve_through_radix(root: &Trie, board: &RadixBoggleBoard, i: usize) -> Solution {
+    let mut word = Vec::with_capacity(64);
+    let mut path = Vec::with_capacity(64);
+    let mut solution = Solution::new();
+    for (trie, v) in root.iter() {
+        word.push(v);
+        for pos in board.any(v) {
+            if pos == i || distance(board.width(), pos, i) <= trie.height() {
+                path.push(pos);
+                descend_through_radix(trie, board, i, pos == i, &mut word, &mut path, &mut solution);
+                path.pop();
+            }
+        }
+        word.pop();
+    }
+    solution
+}
+
+/*
+ * Like descend_radix, but only records words whose path passes through the
+ * target cell, and gives up on a branch once the longest word left in the
+ * trie can't reach the target anymore.
+ */
+
+fn descend_through_radix(parent: &Trie, board: &RadixBoggleBoard, target: usize, through: bool,
+                         word: &mut Vec<u8>, path: &mut Vec<usize>, solution: &mut Solution) {
+    for (trie, i) in parent.iter() {
+        word.push(i);
+
+        for pos in board.neighbors(*path.last().unwrap(), i) {
+            let through = through || pos == target;
+
+            if !path.contains(&pos) && (through || distance(board.width(), pos, target) <= trie.height()) {
+                path.push(pos);
+
+                if let NodeType::Word(id) = trie.node_type() {
+                    if through {
+                        solution.record(id, word, path);
+                    }
+                }
+
+                descend_through_radix(trie, board, target, through, word, path, solution);
+                path.pop();
+            }
+        }
+        word.pop();
+    }
+}
+
+/*
+ * Looks for one word on the board without solving it, so a player's
+ * submission can be checked in microseconds. The word is spelled as the
+ * player typed it, "qu" being a single Qu cell, and the path spelling it
+ * is returned if there is one.
+ */
+
+#[allow(dead_code)]
+pub fn find_word_radix(board: &RadixBoggleBoard, word: &str) -> Option<Vec<usize>> {
+    let word: Vec<u8> = match boggle_util::normalize(word) {
+        Some(s) => s.bytes().map(|b| boggle_util::ascii_byte_to_idx(b) as u8).collect(),
+        None => return None
+    };
+
+    let mut path = Vec::with_capacity(word.len());
+    if find_path_radix(board, &word, &mut path, None) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/*
+ * Searches for a path spelling word, guided only by its letters.
+ * The path is left in `path` when one is found.
+ */
+
+fn find_path_radix(board: &RadixBoggleBoard, word: &[u8], path: &mut Vec<usize>, avoid: Option<usize>) -> bool {
+    match word.split_first() {
+        Some((&first, rest)) => {
+            for pos in board.any(first) {
+                if Some(pos) != avoid {
+                    path.push(pos);
+                    if follow_path_radix(board, rest, path, avoid) {
+                        return true;
+                    }
+                    path.pop();
+                }
+            }
+            false
+        },
+        None => false
+    }
+}
+
+fn follow_path_radix(board: &RadixBoggleBoard, word: &[u8], path: &mut Vec<usize>, avoid: Option<usize>) -> bool {
+    match word.split_first() {
+        Some((&next, rest)) => {
+            for pos in board.neighbors(*path.last().unwrap(), next) {
+                if Some(pos) != avoid && !path.contains(&pos) {
+                    path.push(pos);
+                    if follow_path_radix(board, rest, path, avoid) {
+                        return true;
+                    }
+                    path.pop();
+                }
+            }
+            false
+        },
+        None => true
+    }
+}
+
+/*
+ * Visits every path spelling a word, guided only by its letters, for callers
+ * that care about all the ways a word can be traced and not just the first.
+ * The word is given as the trie spells it.
+ */
+
+pub fn each_path_radix<F>(board: &RadixBoggleBoard, word: &[u8], mut visit: F) where F: FnMut(&[usize]) {
+    if let Some((&first, rest)) = word.split_first() {
+        let mut path = Vec::with_capacity(word.len());
+        for pos in board.any(first) {
+            path.push(pos);
+            each_follow_radix(board, rest, &mut path, &mut visit);
+            path.pop();
+        }
+    }
+}
+
+fn each_follow_radix<F>(board: &RadixBoggleBoard, word: &[u8], path: &mut Vec<usize>, visit: &mut F) where F: FnMut(&[usize]) {
+    match word.split_first() {
+        Some((&next, rest)) => {
+            for pos in board.neighbors(*path.last().unwrap(), next) {
+                if !path.contains(&pos) {
+                    path.push(pos);
+                    each_follow_radix(board, rest, path, visit);
+                    path.pop();
+                }
+            }
+        },
+        None => visit(path)
+    }
+}
+
+/// Number of king moves between two cells
+fn distance(width: usize, a: usize, b: usize) -> usize {
+    let (ax, ay) = (a % width, a / width);
+    let (bx, by) = (b % width, b / width);
+    max(ax.abs_diff(bx), ay.abs_diff(by))
+}
+
+
+#[cfg(test)]
+mod test {
+    use rand::Rng;
+    use rand::SeedableRng;
+    use rand::XorShiftRng;
+
+    use trie::Trie;
+    use boggle::*;
+    use super::*;
+
+    const WORDS: [&str; 24] = [
+        "ate", "eat", "tea", "tee", "net", "ten", "tin", "nit", "rat", "tar",
+        "art", "star", "rats", "tars", "rest", "nest", "sent", "tent", "teen",
+        "train", "stain", "satin", "inert", "enter",
+    ];
+
+    fn trie() -> Trie {
+        let mut trie = Trie::new();
+        for (id, word) in WORDS.iter().enumerate() {
+            trie.insert(word, id);
+        }
+        trie
+    }
+
+    fn random_cell<R: Rng>(rng: &mut R) -> Cell {
+        let letters = b"aeinrst";
+        match rng.gen_range(0, 12) {
+            0 => HOLE,
+            1 => Cell::wildcard(),
+            _ => Cell::letter(letters[rng.gen_range(0, letters.len())] - b'a'),
+        }
+    }
+
+    fn ids(solution: &Solution) -> Vec<usize> {
+        let mut ids: Vec<usize> = solution.iter().map(|f| f.id).collect();
+        ids.sort();
+        ids
+    }
+
+    fn path_counts(solution: &Solution) -> Vec<(usize, usize)> {
+        let mut counts: Vec<(usize, usize)> = solution.iter().map(|f| (f.id, f.paths)).collect();
+        counts.sort();
+        counts
+    }
+
+    fn assert_valid_paths(board: &RadixBoggleBoard, solution: &Solution) {
+        for found in solution.iter() {
+            assert_eq!(found.word.len(), found.path.len());
+            for (k, (&v, &pos)) in found.word.iter().zip(found.path.iter()).enumerate() {
+                assert!(board.get(pos).contains(v));
+                assert!(!found.path[..k].contains(&pos));
+                if k > 0 {
+                    assert!(board.neighbors(found.path[k - 1], v).any(|n| n == pos));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn resolve_matches_full_solve() {
+        let trie = trie();
+        let mut rng = XorShiftRng::from_seed([4, 3, 2, 1]);
+
+        let (width, height) = (6, 5);
+        let mut simple = SimpleBoggleBoard::new(width, height);
+        for i in 0..width * height {
+            simple.set(i, random_cell(&mut rng));
+        }
+        let mut board = RadixBoggleBoard::from(&simple);
+        let mut solution = solve_radix(&trie, &board);
+
+        for _ in 0..300 {
+            let before = ids(&solution);
+            let i = rng.gen_range(0, width * height);
+            let delta = resolve_radix(&trie, &mut board, &mut solution, i, random_cell(&mut rng));
+
+            let expected = solve_radix(&trie, &board);
+            assert_eq!(ids(&solution), ids(&expected));
+            assert_eq!(path_counts(&solution), path_counts(&expected));
+            assert_valid_paths(&board, &solution);
+
+            for found in delta.added.iter() {
+                assert!(!before.contains(&found.id) && expected.contains(found.id));
+            }
+            for found in delta.removed.iter() {
+                assert!(before.contains(&found.id) && !expected.contains(found.id));
+            }
+            assert_eq!(before.len() + delta.added.len() - delta.removed.len(), expected.len());
+        }
+    }
+
+    #[test]
+    fn counts_every_path() {
+        let board = RadixBoggleBoard::from(&SimpleBoggleBoard::read("tea\nate".lines()).unwrap());
+        let solution = solve_radix(&trie(), &board);
+
+        let tea = solution.iter().find(|f| f.word == [19, 4, 0]).unwrap();
+        assert_eq!(tea.paths, 5);
+        assert_eq!(tea.path, vec![0, 1, 2]);
+        let tee = solution.iter().find(|f| f.word == [19, 4, 4]).unwrap();
+        assert_eq!(tee.paths, 3);
+    }
+
+    #[test]
+    fn finds_words_on_the_board() {
+        let simple = SimpleBoggleBoard::read("qet\nena\n?.s".lines()).unwrap();
+        let board = RadixBoggleBoard::from(&simple);
+
+        assert_eq!(find_word_radix(&board, "queen"), Some(vec![0, 1, 3, 4]));
+        assert_eq!(find_word_radix(&board, "ANTE"), Some(vec![5, 4, 2, 1]));
+        assert_eq!(find_word_radix(&board, "tent"), Some(vec![2, 1, 4, 6]));
+        assert_eq!(find_word_radix(&board, "tenets"), None);
+        assert_eq!(find_word_radix(&board, "seat"), None);
+        assert_eq!(find_word_radix(&board, "qet"), None);
+        assert_eq!(find_word_radix(&board, "zen"), Some(vec![6, 3, 4]));
+    }
+}
@@ -0,0 +1,209 @@
+/* Copyright 2017 Joel Pedraza
+ *
+ * Redistribution and use in source and binary forms, with or without
+ * modification, are permitted provided that the following conditions are met:
+ *
+ * 1. Redistributions of source code must retain the above copyright notice,
+ *    this list of conditions and the following disclaimer.
+ *
+ * 2. Redistributions in binary form must reproduce the above copyright notice,
+ *    this list of conditions and the following disclaimer in the documentation
+ *    and/or other materials provided with the distribution.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE
+ * ARE DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE
+ * LIABLE FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR
+ * CONSEQUENTIAL DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF
+ * SUBSTITUTE GOODS OR SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS
+ * INTERRUPTION) HOWEVER CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN
+ * CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE)
+ * ARISING IN ANY WAY OUT OF THE USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE
+ * POSSIBILITY OF SUCH DAMAGE.
+ */
+
+/*
+ * Renders a board as an SVG picture, for puzzle pages and bug reports
+ *
+ * Dice are drawn as rounded tiles, blanks in their own color. Word paths
+ * are drawn over the tiles as arrows from the first letter to the last,
+ * each in the next color of the style. In heatmap mode each tile is shaded
+ * by a value per cell, e.g. how many words pass through it.
+ *
+ * Only square grids are drawn, as that's the only board topology we have.
+ */
+
+use std::fmt::Write;
+
+use boggle::*;
+use boggle_util;
+use heatmap::Heatmap;
+use solver::Solution;
+
+const CELL: f64 = 60.0;
+const GAP: f64 = 6.0;
+const MARGIN: f64 = 12.0;
+
+/// Colors, as anything SVG accepts: names, #rgb, rgb(...)
+#[derive(Clone, Debug)]
+pub struct Style {
+    pub background: String,
+    pub tile: String,
+    pub blank: String,
+    pub text: String,
+    pub heat: String,
+    /// Cycled through, one per word path
+    pub paths: Vec<String>,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            background: "#2b3a55".to_owned(),
+            tile: "#f4ecd8".to_owned(),
+            blank: "#d9e8f5".to_owned(),
+            text: "#1d1d1d".to_owned(),
+            heat: "#e4572e".to_owned(),
+            paths: vec!["#e4572e".to_owned(), "#17bebb".to_owned(), "#76b041".to_owned(), "#ffc914".to_owned()],
+        }
+    }
+}
+
+/*
+ * Draws the board with the paths over it. When heat is given it holds one
+ * value per cell, and tiles are shaded in proportion to the largest value.
+ */
+
+pub fn render(board: &SimpleBoggleBoard, paths: &[Vec<usize>], heat: Option<&[f64]>, style: &Style) -> String {
+    let width = MARGIN * 2.0 + board.width() as f64 * (CELL + GAP) - GAP;
+    let height = MARGIN * 2.0 + board.height() as f64 * (CELL + GAP) - GAP;
+    let mut svg = String::new();
+
+    // Writing to a String can't fail
+    let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#, width, height, width, height);
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" rx="8" fill="{}"/>"#, escape(&style.background));
+
+    let _ = writeln!(svg, "<defs>");
+    for (k, color) in style.paths.iter().enumerate() {
+        let _ = writeln!(svg, r#"<marker id="arrow{}" viewBox="0 0 10 10" refX="5" refY="5" markerWidth="4" markerHeight="4" orient="auto"><path d="M0,0 L10,5 L0,10 z" fill="{}"/></marker>"#,
+                         k, escape(color));
+    }
+    let _ = writeln!(svg, "</defs>");
+
+    let max_heat = heat.map(|heat| heat.iter().cloned().fold(0.0, f64::max)).unwrap_or(0.0);
+    for (i, cell) in board.iter().enumerate() {
+        if *cell == HOLE {
+            continue;
+        }
+        let (x, y) = corner(board.width(), i);
+        let fill = if cell.is_letter() { &style.tile } else { &style.blank };
+        let _ = writeln!(svg, r#"<rect x="{}" y="{}" width="{}" height="{}" rx="8" fill="{}"/>"#, x, y, CELL, CELL, escape(fill));
+
+        if let Some(heat) = heat {
+            if max_heat > 0.0 && heat[i] > 0.0 {
+                let _ = writeln!(svg, r#"<rect x="{}" y="{}" width="{}" height="{}" rx="8" fill="{}" fill-opacity="{:.3}"/>"#,
+                                 x, y, CELL, CELL, escape(&style.heat), 0.85 * heat[i] / max_heat);
+            }
+        }
+
+        let label = label(cell);
+        let size = if label.len() > 2 { CELL * 0.8 / label.len() as f64 + 6.0 } else { CELL * 0.5 };
+        let _ = writeln!(svg, r#"<text x="{}" y="{}" font-family="sans-serif" font-weight="bold" font-size="{:.1}" text-anchor="middle" dominant-baseline="central" fill="{}">{}</text>"#,
+                         x + CELL / 2.0, y + CELL / 2.0, size, escape(&style.text), label);
+    }
+
+    for (k, path) in paths.iter().enumerate() {
+        if path.is_empty() || style.paths.is_empty() {
+            continue;
+        }
+        let color = escape(&style.paths[k % style.paths.len()]);
+        let marker = k % style.paths.len();
+
+        // Spread overlapping paths apart a little
+        let offset = (k as f64 - (paths.len() - 1) as f64 / 2.0) * 5.0;
+        let points: Vec<String> = path.iter().map(|&i| {
+            let (x, y) = center(board.width(), i);
+            format!("{},{}", x + offset, y + offset)
+        }).collect();
+
+        let (x, y) = center(board.width(), path[0]);
+        let _ = writeln!(svg, r#"<circle cx="{}" cy="{}" r="8" fill="{}" fill-opacity="0.8"/>"#, x + offset, y + offset, color);
+        let _ = writeln!(svg, r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="6" stroke-opacity="0.8" stroke-linecap="round" stroke-linejoin="round" marker-end="url(#arrow{})"/>"#,
+                         points.join(" "), color, marker);
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// How many words have a path through each cell, for the heatmap mode
+pub fn word_heat(board: &RadixBoggleBoard, solution: &Solution) -> Vec<f64> {
+    Heatmap::of(board, solution).words.iter().map(|&n| n as f64).collect()
+}
+
+fn corner(width: usize, i: usize) -> (f64, f64) {
+    (MARGIN + (i % width) as f64 * (CELL + GAP), MARGIN + (i / width) as f64 * (CELL + GAP))
+}
+
+fn center(width: usize, i: usize) -> (f64, f64) {
+    let (x, y) = corner(width, i);
+    (x + CELL / 2.0, y + CELL / 2.0)
+}
+
+/// The face printed on a tile: "A", "Qu", "?" for a blank, or the letters of a restricted one
+fn label(cell: &Cell) -> String {
+    if *cell == Cell::wildcard() {
+        return "?".to_owned();
+    }
+    cell.letters().map(|v| {
+        let face = boggle_util::idx_to_face(v as u8);
+        face[..1].to_uppercase() + &face[1..]
+    }).collect()
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+
+#[cfg(test)]
+mod test {
+    use boggle::SimpleBoggleBoard;
+    use dictionary::Dictionary;
+    use solver;
+    use super::*;
+
+    fn board() -> SimpleBoggleBoard {
+        SimpleBoggleBoard::read("qet\nena\n?.[st]".lines()).unwrap()
+    }
+
+    #[test]
+    fn draws_tiles_and_paths() {
+        let svg = render(&board(), &[vec![0, 1, 3, 4], vec![5, 4, 2]], None, &Style::default());
+
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        // Eight tiles, the hole is left out
+        assert_eq!(svg.matches("<rect x=").count(), 8);
+        assert!(svg.contains(">Qu</text>") && svg.contains(">?</text>") && svg.contains(">ST</text>"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+        assert!(svg.contains(r#"points="39.5,39.5 105.5,39.5 39.5,105.5 105.5,105.5""#));
+        assert!(svg.contains("url(#arrow1)"));
+    }
+
+    #[test]
+    fn shades_tiles_by_heat() {
+        // Either b can end "tab", and both count
+        let dictionary = Dictionary::read(["tab"].iter().map(|w| w.to_string()));
+        let tab = RadixBoggleBoard::from(&SimpleBoggleBoard::read("tab\n..b".lines()).unwrap());
+        let heat = word_heat(&tab, &solver::solve_radix(dictionary.trie(), &tab));
+        assert_eq!(heat, vec![1.0, 1.0, 1.0, 0.0, 0.0, 1.0]);
+
+        let heat = vec![1.0, 2.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+
+        let style = Style { heat: "<red>".to_owned(), ..Style::default() };
+        let svg = render(&board(), &[], Some(&heat), &style);
+        assert_eq!(svg.matches(r#"fill="&lt;red&gt;""#).count(), 4);
+        assert!(svg.contains(r#"fill-opacity="0.850""#) && svg.contains(r#"fill-opacity="0.425""#));
+    }
+}
@@ -34,10 +34,13 @@ use boggle_util;
 use bitset::BitSet32;
 use bitset::IndexIter32;
 
-use std::mem;
+use std::cmp::max;
 
 type Node = Option<Box<Trie>>;
 
+const Q: u8 = b'q' - b'a';
+const U: u8 = b'u' - b'a';
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum NodeType {
     Prefix,
@@ -49,6 +52,9 @@ pub struct Trie {
     node_type: NodeType,
     children: [Node; boggle_util::ALPHABET_SIZE],
     child_set: BitSet32,
+    /// Length of the longest word below this node
+    /// (Used to prune searches that must reach a given cell)
+    height: usize,
 }
 
 impl Trie {
@@ -57,6 +63,7 @@ impl Trie {
             node_type: NodeType::Prefix,
             children: Default::default(),
             child_set: BitSet32::new(),
+            height: 0,
         }
     }
 
@@ -64,8 +71,12 @@ impl Trie {
         self.node_type
     }
 
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
     pub fn insert(&mut self, s: &str, id: usize) -> bool {
-    	if boggle_util::is_alpha(s) {
+    	if boggle_util::is_alpha(s) && !s.is_empty() {
     		self.ins(s.to_lowercase().as_bytes(), id);
             true
     	} else {
@@ -74,12 +85,13 @@ impl Trie {
     }
 
     #[inline]
-    fn ins(&mut self, s: &[u8], id: usize) -> () {
+    fn ins(&mut self, s: &[u8], id: usize) {
         let first = boggle_util::ascii_byte_to_idx(s[0]);
+        self.height = max(self.height, s.len());
 
         if self.children[first].is_none() {
             self.child_set.add(first as u32);
-            mem::replace(&mut (self.children[first]), Some(Box::new(Trie::new())));
+            self.children[first] = Some(Box::new(Trie::new()));
         }
 
         let child = self.children[first].as_mut().unwrap();
@@ -91,9 +103,56 @@ impl Trie {
         }
     }
 
+    /*
+     * Unmarks a word, returning its id. Longer words through it are kept,
+     * branches left with no words are pruned, and heights brought up to date.
+     */
+
+    pub fn remove(&mut self, s: &str) -> Option<usize> {
+        if boggle_util::is_alpha(s) && !s.is_empty() {
+            self.rm(s.to_lowercase().as_bytes())
+        } else {
+            None
+        }
+    }
+
+    fn rm(&mut self, s: &[u8]) -> Option<usize> {
+        let first = boggle_util::ascii_byte_to_idx(s[0]);
+
+        let (removed, empty) = match self.children[first].as_mut() {
+            Some(child) => {
+                let removed = if s.len() == 1 {
+                    match child.node_type {
+                        NodeType::Word(id) => {
+                            child.node_type = NodeType::Prefix;
+                            Some(id)
+                        },
+                        NodeType::Prefix => None
+                    }
+                } else {
+                    child.rm(&s[1..])
+                };
+                (removed, child.node_type == NodeType::Prefix && child.height == 0)
+            },
+            None => return None
+        };
+
+        if removed.is_some() {
+            if empty {
+                self.children[first] = None;
+                self.child_set.remove(first as u32);
+            }
+            self.height = self.iter()
+                .map(|(child, _)| if child.height > 0 { child.height + 1 } else { 1 })
+                .max()
+                .unwrap_or(0);
+        }
+        removed
+    }
+
     #[allow(dead_code)]
     pub fn contains(&self, s: &str) -> Option<NodeType> {
-        if boggle_util::is_alpha(s) {
+        if boggle_util::is_alpha(s) && !s.is_empty() {
             self.cns(s.to_lowercase().as_bytes())
         } else {
             None
@@ -116,9 +175,229 @@ impl Trie {
         }
     }
 
-    pub fn iter(&self) -> TrieIterator {
+    /// The node a prefix leads to, the empty prefix being this node
+    pub fn get(&self, prefix: &str) -> Option<&Trie> {
+        if !boggle_util::is_alpha(prefix) {
+            return None;
+        }
+        let mut node = self;
+        for b in prefix.to_lowercase().bytes() {
+            node = node.children[boggle_util::ascii_byte_to_idx(b)].as_ref()?;
+        }
+        Some(node)
+    }
+
+    /// Number of words at or below this node
+    pub fn count(&self) -> usize {
+        let own = match self.node_type {
+            NodeType::Word(_) => 1,
+            NodeType::Prefix => 0,
+        };
+        own + self.iter().map(|(child, _)| child.count()).sum::<usize>()
+    }
+
+    pub fn iter(&self) -> TrieIterator<'_> {
         TrieIterator::new(self)
     }
+
+    /// Every word starting with a prefix, in alphabetical order
+    pub fn prefixed(&self, prefix: &str) -> Option<Words<'_>> {
+        self.get(prefix).map(|node| Words::new(node, prefix.to_lowercase().into_bytes()))
+    }
+
+    /// Every word matching a pattern, in alphabetical order
+    pub fn matching(&self, pattern: Pattern) -> Matches<'_> {
+        Matches::new(self, pattern)
+    }
+}
+
+
+/// A step of a pattern
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token {
+    Letter(u8),
+    /// '?' or '.', any one letter
+    Any,
+    /// '*', any run of letters, including none
+    Run,
+}
+
+/*
+ * A crossword style pattern, "c?t*" or "..qu..", matched against the trie by
+ * stepping a set of pattern positions along each branch, so every word is
+ * visited once however many ways the runs could split it.
+ *
+ * Positions count letters as a player spells them. The trie holds each "qu"
+ * as a single 'q', so stepping on a 'q' reads a 'q' and then a 'u'.
+ */
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    tokens: Vec<Token>,
+}
+
+impl Pattern {
+    /// Positions are kept in a u64, the last bit meaning the whole pattern matched
+    const MAX_LEN: usize = 63;
+
+    pub fn parse(s: &str) -> Result<Pattern, &'static str> {
+        let mut tokens = Vec::with_capacity(s.len());
+        for c in s.chars() {
+            let token = match c {
+                '?' | '.' => Token::Any,
+                '*' if tokens.last() == Some(&Token::Run) => continue,
+                '*' => Token::Run,
+                c => Token::Letter(boggle_util::char_to_idx(c).ok_or("Patterns may only hold letters, '?', '.' and '*'")?),
+            };
+            tokens.push(token);
+        }
+        if tokens.len() > Pattern::MAX_LEN {
+            return Err("Pattern is too long");
+        }
+        Ok(Pattern { tokens })
+    }
+
+    /// Adds the positions reachable by skipping runs
+    fn close(&self, mut positions: u64) -> u64 {
+        for (i, &token) in self.tokens.iter().enumerate() {
+            if token == Token::Run && positions & (1 << i) != 0 {
+                positions |= 1 << (i + 1);
+            }
+        }
+        positions
+    }
+
+    /// The positions reached by stepping on one more trie letter
+    fn step(&self, positions: u64, v: u8) -> u64 {
+        let next = self.read(positions, v);
+        if v == Q {
+            self.read(next, U)
+        } else {
+            next
+        }
+    }
+
+    /// The positions reached by reading one more letter
+    fn read(&self, positions: u64, v: u8) -> u64 {
+        let mut next = 0;
+        for (i, &token) in self.tokens.iter().enumerate() {
+            if positions & (1 << i) != 0 {
+                next |= match token {
+                    Token::Letter(l) if l == v => 1 << (i + 1),
+                    Token::Letter(_) => 0,
+                    Token::Any => 1 << (i + 1),
+                    Token::Run => 1 << i,
+                };
+            }
+        }
+        self.close(next)
+    }
+
+    fn accepts(&self, positions: u64) -> bool {
+        positions & (1 << self.tokens.len()) != 0
+    }
+}
+
+
+/// Lazily walks the words below a node, depth first
+pub struct Words<'a> {
+    stack: Vec<TrieIterator<'a>>,
+    word: Vec<u8>,
+    /// The node itself, when it's a word
+    first: Option<usize>,
+}
+
+impl<'a> Words<'a> {
+    fn new(node: &'a Trie, prefix: Vec<u8>) -> Words<'a> {
+        Words {
+            stack: vec![node.iter()],
+            word: prefix,
+            first: match node.node_type {
+                NodeType::Word(id) => Some(id),
+                NodeType::Prefix => None,
+            },
+        }
+    }
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = (String, usize);
+
+    fn next(&mut self) -> Option<(String, usize)> {
+        if let Some(id) = self.first.take() {
+            return Some((spelling(&self.word), id));
+        }
+        loop {
+            let next = self.stack.last_mut()?.next();
+            match next {
+                Some((child, v)) => {
+                    self.word.push(b'a' + v);
+                    self.stack.push(child.iter());
+                    if let NodeType::Word(id) = child.node_type {
+                        return Some((spelling(&self.word), id));
+                    }
+                },
+                None => {
+                    self.stack.pop();
+                    self.word.pop();
+                }
+            }
+        }
+    }
+}
+
+
+/// Lazily walks the words matching a pattern, pruning branches no position survives
+pub struct Matches<'a> {
+    pattern: Pattern,
+    stack: Vec<(TrieIterator<'a>, u64)>,
+    word: Vec<u8>,
+}
+
+impl<'a> Matches<'a> {
+    fn new(trie: &'a Trie, pattern: Pattern) -> Matches<'a> {
+        let start = pattern.close(1);
+        Matches {
+            pattern,
+            stack: vec![(trie.iter(), start)],
+            word: Vec::new(),
+        }
+    }
+}
+
+impl<'a> Iterator for Matches<'a> {
+    type Item = (String, usize);
+
+    fn next(&mut self) -> Option<(String, usize)> {
+        loop {
+            let (next, positions) = {
+                let &mut (ref mut iter, positions) = self.stack.last_mut()?;
+                (iter.next(), positions)
+            };
+            match next {
+                Some((child, v)) => {
+                    let positions = self.pattern.step(positions, v);
+                    if positions == 0 {
+                        continue;
+                    }
+                    self.word.push(b'a' + v);
+                    self.stack.push((child.iter(), positions));
+                    if let NodeType::Word(id) = child.node_type {
+                        if self.pattern.accepts(positions) {
+                            return Some((spelling(&self.word), id));
+                        }
+                    }
+                },
+                None => {
+                    self.stack.pop();
+                    self.word.pop();
+                }
+            }
+        }
+    }
+}
+
+fn spelling(word: &[u8]) -> String {
+    String::from_utf8_lossy(word).into_owned()
 }
 
 
@@ -130,7 +409,7 @@ pub struct TrieIterator<'a> {
 impl<'a> TrieIterator<'a> {
     fn new(trie: &'a Trie) -> TrieIterator<'a> {
         TrieIterator {
-            trie: trie,
+            trie,
             iter: trie.child_set.iter_ones(),
         }
     }
@@ -157,11 +436,12 @@ impl<'a> Iterator for TrieIterator<'a> {
 
 
 #[cfg(test)]
+#[allow(clippy::char_lit_as_u8)]
 mod test{
 
-    use std::str;
     use super::Trie;
     use super::NodeType;
+    use super::Pattern;
 
     #[test]
     fn valid_words_are_inserted() {
@@ -169,8 +449,11 @@ mod test{
 
         assert_eq!(trie.contains("a"), None);
         assert_eq!(trie.contains("abba"), None);
+        assert_eq!(trie.contains(""), None);
 
         assert!(trie.insert("abba", 0));
+        assert!(!trie.insert("", 1));
+        assert_eq!(trie.contains(""), None);
 
         assert_eq!(trie.contains("a"), Some(NodeType::Prefix));
         assert_eq!(trie.contains("ab"), Some(NodeType::Prefix));
@@ -186,7 +469,7 @@ mod test{
         for s in ('\u{0}' as u8 .. 'A' as u8)
                  .chain('[' as u8 .. 'a' as u8)
                  .chain('{' as u8 .. '\u{ff}' as u8)
-                 .map(|b| unsafe { str::from_utf8_unchecked(&[b]) }.to_owned() ) {
+                 .map(|b| (b as char).to_string()) {
             id += 1;
             assert!(!trie.insert(&s, id));
             assert_eq!(trie.contains(&s), None);
@@ -205,4 +488,66 @@ mod test{
         assert_eq!(trie.contains("b"), Some(NodeType::Word(1)));
         assert_eq!(trie.contains("B"), Some(NodeType::Word(1)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn height_is_longest_word_below() {
+        let mut trie = Trie::new();
+
+        assert_eq!(trie.height(), 0);
+        trie.insert("abc", 0);
+        trie.insert("ab", 1);
+        trie.insert("bcdef", 2);
+        assert_eq!(trie.height(), 5);
+
+        let (a, _) = trie.iter().next().unwrap();
+        assert_eq!(a.height(), 2);
+    }
+
+    #[test]
+    fn remove_keeps_longer_words() {
+        let mut trie = Trie::new();
+        trie.insert("ass", 0);
+        trie.insert("assess", 1);
+        trie.insert("bcdefg", 2);
+
+        assert_eq!(trie.remove("ass"), Some(0));
+        assert_eq!(trie.remove("ass"), None);
+        assert_eq!(trie.remove("as"), None);
+        assert_eq!(trie.contains("ass"), Some(NodeType::Prefix));
+        assert_eq!(trie.contains("assess"), Some(NodeType::Word(1)));
+
+        // Removing the longest word prunes its branch and lowers the height
+        assert_eq!(trie.height(), 6);
+        assert_eq!(trie.remove("bcdefg"), Some(2));
+        assert_eq!(trie.contains("b"), None);
+        assert_eq!(trie.iter().count(), 1);
+
+        assert_eq!(trie.remove("assess"), Some(1));
+        assert_eq!(trie.height(), 0);
+        assert_eq!(trie.iter().count(), 0);
+    }
+
+    #[test]
+    fn walks_prefixes_and_patterns() {
+        let mut trie = Trie::new();
+        for (id, word) in ["cat", "cart", "cast", "coat", "cot", "dog", "caca"].iter().enumerate() {
+            trie.insert(word, id);
+        }
+
+        let words = |iter: &mut dyn Iterator<Item=(String, usize)>| iter.map(|(s, _)| s).collect::<Vec<String>>();
+        assert_eq!(words(&mut trie.prefixed("ca").unwrap()), vec!["caca", "cart", "cast", "cat"]);
+        assert_eq!(words(&mut trie.prefixed("cat").unwrap()), vec!["cat"]);
+        assert!(trie.prefixed("cb").is_none());
+        assert_eq!(trie.get("c").unwrap().count(), 6);
+        assert_eq!(trie.count(), 7);
+
+        let matching = |p: &str| words(&mut trie.matching(Pattern::parse(p).unwrap()));
+        assert_eq!(matching("c?t"), vec!["cat", "cot"]);
+        assert_eq!(matching("c*t"), vec!["cart", "cast", "cat", "coat", "cot"]);
+        // Runs that could split a word several ways still give it once
+        assert_eq!(matching("*a*"), vec!["caca", "cart", "cast", "cat", "coat"]);
+        assert_eq!(matching("...."), vec!["caca", "cart", "cast", "coat"]);
+        assert_eq!(trie.matching(Pattern::parse("*").unwrap()).take(2).count(), 2);
+        assert!(Pattern::parse("c-t").is_err());
+    }
+}